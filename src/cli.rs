@@ -1,6 +1,8 @@
 use clap::{Parser, Subcommand, CommandFactory};
 use clap_complete::{generate, Shell};
+use crate::error::{CmdxError, Result};
 use std::io;
+use std::path::Path;
 
 const LONG_ABOUT: &str = "\
 Your command memory, without memorization.
@@ -35,6 +37,18 @@ pub struct Cli {
     /// Command path for direct access (e.g., cmdx docker/prune)
     #[arg(value_name = "PATH")]
     pub path: Option<String>,
+
+    /// Print diagnostic output (config, store paths, matching, clipboard tools)
+    #[arg(short, long, global = true)]
+    pub verbose: bool,
+
+    /// Suppress success messages; errors are still printed
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    pub quiet: bool,
+
+    /// When to color output: auto (a terminal, unless NO_COLOR is set), always or never
+    #[arg(long, global = true, value_name = "WHEN", default_value = "auto", value_parser = ["auto", "always", "never"])]
+    pub color: String,
 }
 
 impl Cli {
@@ -42,6 +56,30 @@ impl Cli {
         let mut cmd = Cli::command();
         generate(shell, &mut cmd, "cmdx", &mut io::stdout());
     }
+
+    /// Print the roff man page for cmdx, or for one subcommand, to stdout.
+    pub fn generate_man(subcommand: Option<&str>) -> Result<()> {
+        let mut cmd = Cli::command();
+        // Building sets subcommand names like "cmdx-run" for the page titles
+        cmd.build();
+
+        let page = match subcommand {
+            Some(name) => cmd
+                .find_subcommand(name)
+                .cloned()
+                .ok_or_else(|| CmdxError::UnknownSubcommand(name.to_string()))?,
+            None => cmd,
+        };
+        clap_mangen::Man::new(page).render(&mut io::stdout())?;
+        Ok(())
+    }
+
+    /// Write cmdx.1 and a cmdx-<subcommand>.1 page for every subcommand to `dir`.
+    pub fn generate_man_pages(dir: &Path) -> Result<()> {
+        std::fs::create_dir_all(dir)?;
+        clap_mangen::generate_to(Cli::command(), dir)?;
+        Ok(())
+    }
 }
 
 #[derive(Subcommand)]
@@ -53,6 +91,9 @@ Initialize the command store.
 Creates the configuration directory (~/.config/cmdx) and an empty command store.
 Run this once before using other commands.
 
+If ~/.config/cmdx/backup.json exists (e.g. from 'cmdx export'), init offers to
+import it. The question is only asked at a terminal.
+
 EXAMPLE:
     cmdx init")]
     Init,
@@ -63,16 +104,42 @@ Add a new command to the store.
 
 Commands are organized in a hierarchical path structure using '/' as separator.
 If COMMAND is omitted, opens $EDITOR to enter the command interactively.
+A notice is printed if the same command text is already stored elsewhere.
 
 EXAMPLES:
     cmdx add docker/prune \"docker system prune -af\" -e \"Remove unused containers\"
     cmdx add git/stash/pop \"git stash pop\"
     cmdx add k8s/pods \"kubectl get pods -A\" -e \"List all pods\"
     cmdx add my/cmd                              # Opens editor for input
-    cmdx add docker/prune \"...\" --force         # Overwrite existing")]
+    cmdx add docker/prune \"...\" --force         # Overwrite existing
+    cmdx add docker/clean --from docker-cleanup  # Seed from a built-in template
+    cmdx add --auto-path \"docker system prune\"   # Suggests docker/system-prune
+    cmdx add net/ip \"ip -br a\" --dry-run        # Print the file instead of writing it
+    cmdx add tools/rg \"rg -n TODO\" --auto-explain  # Offer rg's --help summary
+    cmdx add build/all \"$(pbpaste)\" --replace-newlines
+    cmdx add --batch seed.tsv                   # One command per TSV line
+    cmdx add db/migrate \"make migrate\" --after db/setup  # Manual order in ls
+
+--batch FILE adds every line of a tab-separated file ('-' for stdin):
+    path<TAB>command[<TAB>explanation]
+Blank lines and lines starting with '#' are skipped; a bad line is reported
+and the rest are still added. --force overwrites existing commands.
+
+Commands are stored on one line. --replace-newlines joins a multi-line
+command with '; ' (or spaces, with core.newline_join = \"continuation\").
+At the Command: prompt, end a line with '\\' to continue on the next.
+
+--after PATH places the new command right after PATH (same category) by
+numbering the category's commands with 'order:' meta lines. 'cmdx ls' uses
+that order when display.order = \"manual\".
+
+--expires WHEN marks a throwaway command: a duration from now (7d, 12h) or
+a date (2026-11-01). 'ls' and 'find' tag it once expired and
+'cmdx prune --expired' removes it.")]
     Add {
-        /// Command path (e.g., docker/prune, git/stash/pop)
-        path: String,
+        /// Command path (e.g., docker/prune, git/stash/pop), or the command with --auto-path
+        #[arg(required_unless_present = "batch")]
+        path: Option<String>,
 
         /// The command to store (opens $EDITOR if omitted)
         #[arg(value_name = "COMMAND")]
@@ -85,18 +152,94 @@ EXAMPLES:
         /// Overwrite if the command already exists
         #[arg(short, long)]
         force: bool,
+
+        /// Use a built-in template (see 'cmdx templates')
+        #[arg(long, value_name = "TEMPLATE", conflicts_with = "command")]
+        from: Option<String>,
+
+        /// Refuse to add if the same command text exists at another path
+        #[arg(long)]
+        no_duplicates: bool,
+
+        /// Skip the duplicate scan (faster on very large stores)
+        #[arg(long, conflicts_with = "no_duplicates")]
+        skip_duplicate_check: bool,
+
+        /// Treat the argument as the command and suggest a path from it
+        #[arg(long, conflicts_with = "from")]
+        auto_path: bool,
+
+        /// Print the file that would be written, without touching the store
+        #[arg(long, visible_alias = "no-store")]
+        dry_run: bool,
+
+        /// Run '<tool> --help' (2s timeout) and offer its first line as the explanation
+        #[arg(long, conflicts_with = "explain")]
+        auto_explain: bool,
+
+        /// Join a multi-line COMMAND into one line (see core.newline_join)
+        #[arg(long)]
+        replace_newlines: bool,
+
+        /// Order the new command right after this one in its category
+        #[arg(long, value_name = "PATH")]
+        after: Option<String>,
+
+        /// Mark the command temporary: a duration (7d, 12h) or a date (2026-11-01)
+        #[arg(long, value_name = "WHEN")]
+        expires: Option<String>,
+
+        /// Add each 'path<TAB>command<TAB>explanation' line of FILE ('-' for stdin)
+        #[arg(
+            long,
+            value_name = "FILE",
+            conflicts_with_all = ["path", "command", "explain", "from", "auto_path", "dry_run", "auto_explain", "after", "expires"]
+        )]
+        batch: Option<String>,
     },
 
     /// Show a command
     #[command(long_about = "\
 Display a command and its explanation.
 
+Use --copy to also copy the command to the clipboard. With the global -v
+flag the author and creation time are shown too.
+
+--command-only and --explain-only print just that field, uncolored, for
+scripts. --explain-only fails if the command has no explanation, so a
+missing one is never mistaken for an empty string.
+
+When stdout is not a terminal, show prints a single 'path<TAB>command' line
+instead of the colored block, so it can be piped without extra flags.
+
 EXAMPLES:
     cmdx show docker/prune
-    cmdx show git/stash/pop")]
+    cmdx show git/stash/pop
+    cmdx show docker/prune --copy
+    cmdx show backup/rsync --grep delete  # Highlight a flag in a long command
+    eval \"$(cmdx show k8s/ctx --command-only)\"
+
+--grep highlights every case-insensitive occurrence of TERM in the command
+and explanation; nothing else changes, and NO_COLOR turns highlighting off.")]
     Show {
         /// Command path
         path: String,
+
+        /// Also copy the command to clipboard
+        #[arg(short, long)]
+        copy: bool,
+
+        /// Print only the command
+        #[arg(long, conflicts_with = "explain_only")]
+        command_only: bool,
+
+        /// Print only the explanation (error if there is none)
+        #[arg(long)]
+        explain_only: bool,
+
+        /// Highlight occurrences of TERM in the command and explanation
+        #[arg(long, value_name = "TERM", conflicts_with_all = ["command_only", "explain_only"])]
+        grep: Option<String>,
     },
 
     /// List commands (tree view)
@@ -105,15 +248,59 @@ EXAMPLES:
 List all commands in a tree view.
 
 Optionally filter by path prefix to show only commands under a specific category.
+A prefix that is not a category also matches partial names ('git/sta' lists
+git/stash/* and git/status); end it with '/' to match a category only.
+A tree taller than the terminal is shown through $PAGER (default: less);
+use --no-pager to print it directly. Piped output is never paged.
 
 EXAMPLES:
     cmdx ls                    # List all commands
     cmdx list                  # Same as above
     cmdx ls docker             # List only docker/* commands
-    cmdx ls git/stash          # List only git/stash/* commands")]
+    cmdx ls git/stash          # List only git/stash/* commands
+    cmdx ls git/sta            # git/stash/*, git/status, ...
+    cmdx ls --categories       # Top-level categories with counts
+    cmdx ls --categories --by-count
+    cmdx ls --json             # Tree as JSON
+    cmdx ls docker --json-full # JSON tree including commands at leaves
+    cmdx ls git -l             # Each leaf as 'name — command'
+    cmdx ls --files            # Each command with its file on disk
+    cmdx ls --files --plain    # 'path<TAB>file' lines for scripts")]
     List {
         /// Filter by path prefix (e.g., 'docker' shows only docker/* commands)
         path: Option<String>,
+
+        /// Show only top-level categories with command counts
+        #[arg(long, visible_alias = "summary")]
+        categories: bool,
+
+        /// Sort categories by command count instead of name
+        #[arg(long, requires = "categories")]
+        by_count: bool,
+
+        /// Print the tree as JSON
+        #[arg(long, conflicts_with = "categories")]
+        json: bool,
+
+        /// Print the tree as JSON, including command and explanation at leaves
+        #[arg(long, conflicts_with = "categories")]
+        json_full: bool,
+
+        /// Never pipe the tree through $PAGER
+        #[arg(long)]
+        no_pager: bool,
+
+        /// Show each command's text (dimmed, truncated) after its name
+        #[arg(short, long, conflicts_with_all = ["categories", "json", "json_full"])]
+        long: bool,
+
+        /// List each command with the absolute path of its file instead of the tree
+        #[arg(long, conflicts_with_all = ["categories", "json", "json_full", "long"])]
+        files: bool,
+
+        /// One uncolored path per line (with --files: 'path<TAB>file')
+        #[arg(long, conflicts_with_all = ["categories", "json", "json_full", "long"])]
+        plain: bool,
     },
 
     /// Fuzzy search commands
@@ -121,14 +308,57 @@ EXAMPLES:
 Search for commands using fuzzy matching.
 
 Searches both command paths and the commands themselves. Returns the best matches.
+Equal scores are ordered by shorter path, then most recently run, then name.
+Use --run or --copy to act on the top match directly; if several matches tie
+for the best score you are asked to pick one. With --interactive the matches
+open in the picker (a single match is copied right away). Results taller than
+the terminal go through $PAGER unless --no-pager is given.
+
+--exec runs a shell command for every match, with {path}, {command} and
+{explanation} replaced by the match's shell-quoted values. End the template
+with ' +' to run it once with all matches' values, like find -exec ... {} +.
+More than 5 matches ask for confirmation unless --force is given.
 
 EXAMPLES:
     cmdx find prune            # Find commands matching 'prune'
     cmdx find \"git stash\"      # Find commands matching 'git stash'
-    cmdx find pods             # Find kubernetes pod commands")]
+    cmdx find pods             # Find kubernetes pod commands
+    cmdx find prune --run      # Run the best match (with confirmation)
+    cmdx find prune --copy     # Copy the best match
+    cmdx find prune -i         # Pick among matches in the TUI, then copy
+    cmdx find git --exec \"echo {path}\"        # One line per match
+    cmdx find git --exec \"echo {path} +\"      # One run with every path")]
     Find {
-        /// Search query (matches against path and command content)
+        /// Search query (matches against path, command and notes)
         query: String,
+
+        /// Run the best match after confirmation
+        #[arg(long, conflicts_with = "copy")]
+        run: bool,
+
+        /// Copy the best match to clipboard
+        #[arg(long)]
+        copy: bool,
+
+        /// Open the matches in the interactive picker
+        #[arg(short, long, conflicts_with_all = ["run", "copy"])]
+        interactive: bool,
+
+        /// Never pipe results through $PAGER
+        #[arg(long)]
+        no_pager: bool,
+
+        /// Run a shell command for each match ({path}, {command}, {explanation})
+        #[arg(long = "exec", value_name = "TEMPLATE", conflicts_with_all = ["run", "copy", "interactive"])]
+        exec_template: Option<String>,
+
+        /// Skip the confirmation for --exec on many matches
+        #[arg(short, long, requires = "exec_template")]
+        force: bool,
+
+        /// Restrict matching to one field; "all" also searches notes at a lower weight
+        #[arg(long, default_value = "all", value_parser = ["all", "path", "command", "notes"])]
+        field: String,
     },
 
     /// Copy command to clipboard
@@ -137,6 +367,8 @@ EXAMPLES:
 Copy a command to the system clipboard.
 
 Supports fuzzy matching - if exact path not found, finds the best match.
+If several matches score almost equally and you're at a terminal, you are
+asked to pick one; --no-prompt always takes the best match.
 Falls back to printing the command if clipboard is unavailable.
 
 Clipboard tool can be configured in ~/.config/cmdx/config.toml:
@@ -150,6 +382,10 @@ EXAMPLES:
     Copy {
         /// Command path or search query
         query: String,
+
+        /// Never ask to disambiguate; use the best fuzzy match
+        #[arg(long)]
+        no_prompt: bool,
     },
 
     /// Execute a command
@@ -157,19 +393,74 @@ EXAMPLES:
 Execute a stored command.
 
 Supports fuzzy matching - if exact path not found, finds the best match.
+If several matches score almost equally and you're at a terminal, you are
+asked to pick one; --no-prompt always takes the best match.
 Use --confirm to review the command before execution.
 
+The 'Running:' banner and prompts are written to stderr, so the executed
+command's stdout can be piped cleanly. Use --raw to drop the banner entirely.
+
 EXAMPLES:
     cmdx run docker/prune      # Execute immediately
     cmdx run docker/prune -c   # Confirm before executing
-    cmdx run prune             # Fuzzy match, runs best match")]
+    cmdx run prune             # Fuzzy match, runs best match
+    cmdx run k8s/pods --raw | grep Running
+    cmdx run deploy --env-file .env.prod  # Set KEY=VALUE vars from a file
+    cmdx run -                 # Run the last executed command again
+    cmdx run gen/secret --copy-output     # Print the output and copy it
+    cmdx run make/test --cwd ~/src/app    # Run in another directory
+    cmdx run ssh/tunnel --interactive-args # Prompt with last values as defaults
+
+A 'cwd: <dir>' line in the command's '## meta' block sets the directory it
+always runs in; --cwd overrides it for one run.
+
+--interactive-args lists every {placeholder} up front, offers the values
+used last time as defaults (Enter keeps one), and shows the filled command
+for confirmation. Values are remembered per command in args.json next to
+config.toml; --reset-args forgets them.")]
     Run {
-        /// Command path or search query
+        /// Command path or search query ('-' for the last run command)
         query: String,
 
         /// Show command and confirm before executing
         #[arg(short, long)]
         confirm: bool,
+
+        /// Only emit the command's own output (no banner)
+        #[arg(long)]
+        raw: bool,
+
+        /// Never ask to disambiguate; use the best fuzzy match
+        #[arg(long)]
+        no_prompt: bool,
+
+        /// Load environment variables from a dotenv-style file
+        #[arg(long, value_name = "PATH")]
+        env_file: Option<String>,
+
+        /// Also copy the command's stdout to the clipboard (not on failure)
+        #[arg(long)]
+        copy_output: bool,
+
+        /// Run in this directory instead of the command's stored cwd
+        #[arg(long, value_name = "DIR")]
+        cwd: Option<String>,
+
+        /// Prompt for all placeholders, defaulting to the values used last time
+        #[arg(long)]
+        interactive_args: bool,
+
+        /// Forget the placeholder values remembered for this command
+        #[arg(long)]
+        reset_args: bool,
+
+        /// Re-run up to N more times while the command exits non-zero
+        #[arg(long, value_name = "N", default_value_t = 0)]
+        retry: u32,
+
+        /// Seconds to wait between retries
+        #[arg(long, value_name = "S", default_value_t = 0, requires = "retry")]
+        retry_delay: u64,
     },
 
     /// Edit a command in $EDITOR
@@ -179,33 +470,65 @@ Open a command file in your default editor ($EDITOR).
 The file format is plain text:
     Line 1: The command itself
     Line 2: Single-line explanation (optional)
+    Optional '## meta' block: 'author: ...' and 'created: ...' lines
+    Optional '## args' block: 'name: description' per {placeholder} ('name?:' if optional)
+    Optional '## output' line followed by example output
+
+If PATH is not an exact command path it is fuzzy matched: a single match is
+opened directly, several open the picker to choose from.
 
 EXAMPLES:
     cmdx edit docker/prune
+    cmdx edit prune                  # Pick among fuzzy matches
     EDITOR=vim cmdx edit git/stash/pop")]
     Edit {
-        /// Command path
+        /// Command path or search query
         path: String,
     },
 
+    /// Edit many explanations at once in $EDITOR
+    #[command(long_about = "\
+Open one editor buffer with a 'path: explanation' line per command, and save
+the explanations you change back to each command.
+
+Lines are matched by path, so reordering them is fine. Deleting a line leaves
+that command unchanged, and lines with unknown paths are skipped.
+
+EXAMPLES:
+    cmdx annotate                  # Every command
+    cmdx annotate --prefix docker  # Only docker/*")]
+    Annotate {
+        /// Only commands under this category
+        #[arg(long, value_name = "P")]
+        prefix: Option<String>,
+    },
+
     /// Remove a command
     #[command(visible_alias = "rm")]
     #[command(long_about = "\
 Remove a command from the store.
 
-Prompts for confirmation unless --force is specified.
+Prompts for confirmation unless --force is specified. Removed commands go
+to the trash (see 'cmdx trash') and can be restored with 'cmdx undo'.
+Use --recursive to remove every command under a category; the affected
+commands are listed before confirming.
 
 EXAMPLES:
     cmdx rm docker/prune       # Prompts for confirmation
     cmdx remove docker/prune   # Same as above
-    cmdx rm docker/prune -f    # Skip confirmation")]
+    cmdx rm docker/prune -f    # Skip confirmation
+    cmdx rm -r docker          # Remove all docker/* commands")]
     Remove {
-        /// Command path
+        /// Command path (or category with --recursive)
         path: String,
 
         /// Skip confirmation prompt
         #[arg(short, long)]
         force: bool,
+
+        /// Remove all commands under the given category
+        #[arg(short, long)]
+        recursive: bool,
     },
 
     /// Move/rename a command
@@ -213,15 +536,42 @@ EXAMPLES:
     #[command(long_about = "\
 Move or rename a command.
 
+Intermediate categories are created as needed. With --interactive, existing
+categories are listed to choose from and the command keeps its name.
+
+--alias leaves a redirect at the old path, so 'cmdx run <old>' keeps working
+and prints a hint to use the new path. Aliases are hidden from ls and find;
+remove one with 'cmdx rm <old>'.
+
 EXAMPLES:
     cmdx mv docker/prune docker/cleanup    # Rename
-    cmdx move git/stash git/saved          # Move to different category")]
+    cmdx move git/stash git/saved          # Move to different category
+    cmdx mv -i docker/prune                # Pick destination category
+    cmdx mv k8s/pods kube/pods --alias     # Old path still resolves
+    cmdx mv old/tool new/tool --dry-run    # Preview, including emptied categories
+
+--dry-run prints the move, any alias and the categories that would be left
+empty and pruned, without changing anything. A destination that already
+exists fails the dry run just as it would the real move.")]
     Move {
         /// Source path
         src: String,
 
         /// Destination path
-        dst: String,
+        #[arg(required_unless_present = "interactive")]
+        dst: Option<String>,
+
+        /// Choose the destination from existing categories
+        #[arg(short, long, conflicts_with = "dst")]
+        interactive: bool,
+
+        /// Leave an alias at the old path that redirects to the new one
+        #[arg(long)]
+        alias: bool,
+
+        /// Show what would move (and which categories would be pruned) without moving
+        #[arg(long)]
+        dry_run: bool,
     },
 
     /// Export all commands to JSON
@@ -230,33 +580,71 @@ Export all commands to a portable JSON file.
 
 Use this to backup your commands or transfer them to another machine.
 Output goes to stdout by default, or to a file with --output.
+With --dir, the store hierarchy is written out as plain command files instead,
+which suits versioning individual commands in a dotfiles repo.
 
 EXAMPLES:
     cmdx export                          # Print JSON to stdout
     cmdx export -o commands.json         # Save to file
     cmdx export > backup.json            # Redirect to file
+    cmdx export --dir ~/dotfiles/cmdx    # Mirror store as files
+    cmdx export --since 7d               # Only commands changed in the last week
+    cmdx export --filter docker --since 24h
+    cmdx export --include-config -o all.json  # Commands plus config.toml
+
+With --filter or --since, the matched commands are listed and you are asked
+to confirm; --force skips the prompt.
 
 The JSON file can be imported with 'cmdx import'.")]
     Export {
         /// Output file (prints to stdout if omitted)
         #[arg(short, long)]
         output: Option<String>,
+
+        /// Write one file per command into this directory
+        #[arg(long, visible_alias = "output-dir", value_name = "DIR", conflicts_with = "output")]
+        dir: Option<String>,
+
+        /// Only export commands under this path prefix
+        #[arg(long, value_name = "PREFIX")]
+        filter: Option<String>,
+
+        /// Only export commands modified within this duration (e.g. 30m, 24h, 7d)
+        #[arg(long, value_name = "DURATION")]
+        since: Option<String>,
+
+        /// Embed config.toml in the JSON export
+        #[arg(long, conflicts_with = "dir")]
+        include_config: bool,
+
+        /// Skip the confirmation prompt for --filter/--since
+        #[arg(short, long)]
+        force: bool,
     },
 
-    /// Import commands from JSON
+    /// Import commands from JSON, or from pet/navi/tldr files
     #[command(long_about = "\
 Import commands from a JSON file.
 
 Use this to restore commands from a backup or transfer from another machine.
 Reads from stdin by default, or from a file with a path argument.
 
+With --from, convert another tool's snippets instead: pet (snippet.toml),
+navi (.cheat files) or tldr (pages). Descriptions become explanations and
+placeholders become {name}; paths are generated from the first tag (or page
+name) and the description.
+
 EXAMPLES:
     cmdx import commands.json            # Import from file
     cmdx import < backup.json            # Import from stdin
     cat backup.json | cmdx import        # Pipe to import
     cmdx import commands.json --force    # Overwrite existing commands
+    cmdx import all.json --include-config  # Also restore config.toml
+    cmdx import shared.json --check      # Validate only, store untouched
+    cmdx import --from pet ~/.config/pet/snippet.toml
+    cmdx import --from navi git.cheat
 
-Use --force to overwrite existing commands.")]
+Use --force to overwrite existing commands (and config, with --include-config).")]
     Import {
         /// Input file (reads from stdin if omitted)
         input: Option<String>,
@@ -264,6 +652,18 @@ Use --force to overwrite existing commands.")]
         /// Overwrite existing commands
         #[arg(short, long)]
         force: bool,
+
+        /// Restore config.toml if the export contains it
+        #[arg(long)]
+        include_config: bool,
+
+        /// Check the export parses and every path is valid, without importing
+        #[arg(long, visible_alias = "check", conflicts_with_all = ["force", "include_config"])]
+        validate_only: bool,
+
+        /// Convert from another tool's format: pet, navi or tldr
+        #[arg(long, value_name = "FORMAT", conflicts_with_all = ["include_config", "validate_only"])]
+        from: Option<String>,
     },
 
     /// Generate shell completions
@@ -290,6 +690,27 @@ SUPPORTED SHELLS:
         shell: Shell,
     },
 
+    /// Generate man pages
+    #[command(long_about = "\
+Generate roff man pages from cmdx's own help.
+
+Without --dir, the page for cmdx (or for SUBCOMMAND) is printed to stdout.
+With --dir, cmdx.1 and one cmdx-<subcommand>.1 page per subcommand are
+written to that directory.
+
+EXAMPLES:
+    cmdx man > ~/.local/share/man/man1/cmdx.1
+    cmdx man run | man -l -
+    cmdx man --dir ~/.local/share/man/man1   # Every page at once")]
+    Man {
+        /// Only this subcommand's page (e.g. 'run')
+        subcommand: Option<String>,
+
+        /// Write all pages into this directory instead of printing one
+        #[arg(long, value_name = "DIR", conflicts_with = "subcommand")]
+        dir: Option<String>,
+    },
+
     /// Interactive fuzzy finder (Telescope-style)
     #[command(visible_alias = "s")]
     #[command(long_about = "\
@@ -302,12 +723,317 @@ KEYBINDINGS:
     Type           Filter commands live
     Up/Down        Navigate results
     Ctrl+k/j       Navigate results (vim-style)
-    Tab/Shift+Tab  Navigate results
+    Tab/Shift+Tab  Navigate results (with --multi: mark and move)
     Enter          Select and copy to clipboard
     Esc/Ctrl+c     Cancel
 
+Commands added, edited or deleted in the picker are listed when it closes.
+
+With --multi, Tab marks several commands; Enter copies all of them, one per
+line (or just the highlighted one if none are marked). Add --export FILE to
+write the marked commands to FILE in 'cmdx export' format instead.
+
 EXAMPLES:
     cmdx pick      # Open interactive picker
-    cmdx s         # Same as above (alias)")]
-    Pick,
+    cmdx s         # Same as above (alias)
+    cmdx pick --multi --export k8s.json   # Curate a subset to share")]
+    Pick {
+        /// Mark several commands with Tab
+        #[arg(long)]
+        multi: bool,
+
+        /// Write the marked commands to FILE as JSON instead of copying them
+        #[arg(long, value_name = "FILE", requires = "multi")]
+        export: Option<String>,
+    },
+
+    /// List built-in command templates
+    #[command(long_about = "\
+List the built-in command templates shipped with cmdx.
+
+Templates are not part of your store. Copy one into the store with
+'cmdx add <path> --from <template>'.
+
+EXAMPLES:
+    cmdx templates
+    cmdx add docker/clean --from docker-cleanup")]
+    Templates,
+
+    /// Show run history of a command
+    #[command(long_about = "\
+Show when a command was run and its exit codes.
+
+Every 'cmdx run' is recorded in ~/.config/cmdx/history.jsonl (one JSON
+object per line). The query is resolved like 'cmdx run': exact path first,
+then the best fuzzy match.
+
+EXAMPLES:
+    cmdx history docker/prune
+    cmdx history prune")]
+    History {
+        /// Command path or search query
+        query: String,
+    },
+
+    /// Upgrade the store to the current format
+    #[command(long_about = "\
+Upgrade the command store to the current on-disk format.
+
+The format version is recorded in a .cmdx-version file in the store root.
+Stores created before versioning are treated as version 1.
+
+EXAMPLE:
+    cmdx migrate")]
+    Migrate,
+
+    /// Check commands against store conventions
+    #[command(long_about = "\
+Check every command against the conventions configured in [lint].
+
+Reports each violation with the offending path and exits non-zero if any
+remain, so it can run in CI. Path issues (uppercase, spaces) can be fixed
+automatically with --fix, which renames the commands.
+
+Rules (~/.config/cmdx/config.toml):
+    [lint]
+    lowercase_paths = true
+    no_spaces = true
+    require_explanation = true
+    max_command_length = 200     # optional
+
+EXAMPLES:
+    cmdx lint
+    cmdx lint --fix")]
+    Lint {
+        /// Rename commands to fix path issues
+        #[arg(long)]
+        fix: bool,
+    },
+
+    /// Re-run the last executed command
+    #[command(long_about = "\
+Run the most recently executed command again, using the history log.
+
+The command is re-read from the store, so edits since the last run are
+picked up. Same as 'cmdx run -'.
+
+EXAMPLES:
+    cmdx rerun                 # Run it again
+    cmdx rerun -c              # Confirm first")]
+    Rerun {
+        /// Show command and confirm before executing
+        #[arg(short, long)]
+        confirm: bool,
+
+        /// Only emit the command's own output (no banner)
+        #[arg(long)]
+        raw: bool,
+
+        /// Load environment variables from a dotenv-style file
+        #[arg(long, value_name = "PATH")]
+        env_file: Option<String>,
+
+        /// Also copy the command's stdout to the clipboard (not on failure)
+        #[arg(long)]
+        copy_output: bool,
+    },
+
+    /// Copy the last copied command again
+    #[command(visible_alias = "paste", long_about = "\
+Copy the most recently copied command to the clipboard again, e.g. after
+something else overwrote it. Copies from 'cmdx cp', 'find --copy' and the
+picker are remembered.
+
+The command is re-read from the store, so edits since are picked up.
+
+EXAMPLES:
+    cmdx last                  # Copy it again
+    cmdx last --print          # Print it instead")]
+    Last {
+        /// Print the command instead of copying it
+        #[arg(long)]
+        print: bool,
+    },
+
+    /// List removed commands
+    #[command(long_about = "\
+List commands removed with 'cmdx rm' or the TUI, newest first.
+
+The number in the first column is what 'cmdx undo' takes.
+
+EXAMPLE:
+    cmdx trash")]
+    Trash,
+
+    /// Restore a removed command
+    #[command(long_about = "\
+Restore a command from the trash to its original path.
+
+Without N the most recently removed command is restored.
+
+EXAMPLES:
+    cmdx undo                  # Restore the last removed command
+    cmdx undo 3                # Restore entry 3 from 'cmdx trash'
+    cmdx undo 2 --force        # Overwrite a command now at that path")]
+    Undo {
+        /// Entry number from 'cmdx trash'
+        #[arg(default_value_t = 1)]
+        n: usize,
+
+        /// Overwrite a command that now exists at the original path
+        #[arg(short, long)]
+        force: bool,
+    },
+
+    /// Permanently delete trashed commands
+    #[command(long_about = "\
+Empty the trash. Prompts for confirmation unless --force is specified.
+
+EXAMPLES:
+    cmdx clean-trash
+    cmdx clean-trash -f")]
+    CleanTrash {
+        /// Skip confirmation prompt
+        #[arg(short, long)]
+        force: bool,
+    },
+
+    /// Remove commands that have expired
+    #[command(long_about = "\
+Remove commands whose expiry date has passed. Set one with 'cmdx add --expires'.
+Removed commands go to the trash, like 'cmdx rm'. Prompts for confirmation
+unless --force is specified.
+
+EXAMPLES:
+    cmdx prune --expired --dry-run   # List what would go
+    cmdx prune --expired
+    cmdx prune --expired -f")]
+    Prune {
+        /// Remove commands past their 'expires:' date
+        #[arg(long, required = true)]
+        expired: bool,
+
+        /// List the expired commands without removing them
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Skip confirmation prompt
+        #[arg(short, long)]
+        force: bool,
+    },
+
+    /// Search and replace text across stored commands
+    #[command(long_about = "\
+Rewrite a substring in every stored command, e.g. after a flag is renamed.
+
+Affected commands are shown as a diff and confirmed before saving. Only the
+command line is changed; explanations are left alone. With --regex, FROM is
+a regular expression and TO may use $1-style capture groups.
+
+EXAMPLES:
+    cmdx replace --cluster --context              # Every command
+    cmdx replace kubectl k --prefix k8s           # Only k8s/*
+    cmdx replace 'v(\\d+)' 'v$1.0' --regex --dry-run")]
+    Replace {
+        /// Text (or pattern with --regex) to find
+        #[arg(allow_hyphen_values = true)]
+        from: String,
+
+        /// Replacement text
+        #[arg(allow_hyphen_values = true)]
+        to: String,
+
+        /// Only touch commands under this category
+        #[arg(long, value_name = "P")]
+        prefix: Option<String>,
+
+        /// Treat FROM as a regular expression
+        #[arg(long)]
+        regex: bool,
+
+        /// Show the changes without saving them
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Skip confirmation prompt
+        #[arg(short, long)]
+        force: bool,
+    },
+
+    /// Show usage statistics from the run history
+    #[command(long_about = "\
+Summarize how often stored commands are run (via 'cmdx run').
+
+With --unused, print the paths of commands that have never been run, one per
+line, ready to pipe into 'cmdx rm'. --older-than limits that to commands whose
+file has not changed within the given duration.
+
+EXAMPLES:
+    cmdx stats
+    cmdx stats --unused
+    cmdx stats --unused --older-than 90d
+    cmdx stats --unused --older-than 180d | xargs -n1 cmdx rm -f
+    cmdx stats --export > usage.json
+    cmdx stats --export --format csv > usage.csv
+
+--export prints one record per command (path, runs, last_used, created) as
+JSON or CSV. Paths found in the history but no longer in the store are
+included with stale set to true.")]
+    Stats {
+        /// List commands that have never been run
+        #[arg(long)]
+        unused: bool,
+
+        /// Only commands not modified within this duration (e.g. 90d, 12w)
+        #[arg(long, value_name = "DURATION", requires = "unused")]
+        older_than: Option<String>,
+
+        /// Print per-command usage data for analysis
+        #[arg(long, conflicts_with = "unused")]
+        export: bool,
+
+        /// Export format
+        #[arg(long, default_value = "json", value_parser = ["json", "csv"], requires = "export")]
+        format: String,
+    },
+
+    /// Interactive prompt for running several cmdx commands
+    #[command(long_about = "\
+Start an interactive session: each line is a cmdx command without the
+leading 'cmdx'. Quote arguments as in a shell. Type 'exit' or press Ctrl+D
+to leave.
+
+EXAMPLE:
+    cmdx repl
+    cmdx> find prune
+    cmdx> run docker/ps
+    cmdx> add net/ip \"ip -br a\" -e \"Brief addresses\"")]
+    Repl,
+
+    /// Serve the store over a local read-only HTTP API
+    #[cfg(feature = "serve")]
+    #[command(long_about = "\
+Expose the store as JSON over HTTP for editor plugins and web frontends.
+
+Endpoints (GET only):
+    /commands              All commands, in the 'cmdx export' format
+    /command/<path>        A single command
+    /search?q=<query>      Fuzzy search results, best first
+
+Binds to 127.0.0.1 unless --host is given. Only available in builds with
+the 'serve' feature (cargo install cmdx --features serve).
+
+EXAMPLES:
+    cmdx serve
+    cmdx serve --port 9000
+    curl 'localhost:8099/search?q=prune'")]
+    Serve {
+        /// Address to bind
+        #[arg(long, default_value = "127.0.0.1")]
+        host: String,
+
+        /// Port to listen on
+        #[arg(short, long, default_value_t = 8099)]
+        port: u16,
+    },
 }
@@ -1,60 +1,409 @@
 use crate::error::{CmdxError, Result};
+use crate::history::parse_timestamp;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::Path;
 
+/// Substrings (lowercase, single-spaced) that mark a command as high risk.
+pub const DANGEROUS_PATTERNS: &[&str] = &[
+    "rm -rf",
+    "rm -fr",
+    "mkfs",
+    "dd if=",
+    "> /dev/sd",
+    ":(){",
+    "chmod -r 777",
+    "git push --force",
+    "git push -f",
+    "git reset --hard",
+    "git clean -fd",
+    "drop table",
+    "drop database",
+    "kubectl delete",
+    "system prune",
+];
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Command {
     pub path: String,
     pub command: String,
     pub explanation: String,
+    /// Optional example output, stored after an `## output` line.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub output: String,
+    /// Optional placeholder descriptions, stored after an `## args` line.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<ArgSpec>,
+    /// Who added the command, from the `## meta` block.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub author: String,
+    /// When the command was added (`YYYY-MM-DD HH:MM:SS`, UTC).
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub created: String,
+    /// Directory `run` executes in (`cwd:` in `## meta`); `~` is expanded.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub cwd: String,
+    /// Shell arguments before the command (`shell_args:` in `## meta`,
+    /// space-separated), overriding `[run] shell_args`.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub shell_args: String,
+    /// Position within its category when `[display] order = "manual"`
+    /// (`order:` in `## meta`); unordered commands sort after ordered ones.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub order: Option<u32>,
+    /// When the command stops being useful (`expires:` in `## meta`, same
+    /// format as `created`); `cmdx prune --expired` removes it after that.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub expires: String,
+    /// Target path when this file is an `@alias` redirect rather than a command.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub alias: String,
+}
+
+/// Metadata for one `{placeholder}`, written as `name: description`
+/// (or `name?: description` when it may be left empty).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArgSpec {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    #[serde(default = "default_required")]
+    pub required: bool,
 }
 
+fn default_required() -> bool {
+    true
+}
+
+impl ArgSpec {
+    fn parse(line: &str) -> Option<Self> {
+        let (name, description) = line.split_once(':').unwrap_or((line, ""));
+        let name = name.trim();
+        let (name, required) = match name.strip_suffix('?') {
+            Some(n) => (n.trim(), false),
+            None => (name, true),
+        };
+        if name.is_empty() {
+            return None;
+        }
+        Some(Self {
+            name: name.to_string(),
+            description: description.trim().to_string(),
+            required,
+        })
+    }
+
+    fn to_line(&self) -> String {
+        let name = if self.required { self.name.clone() } else { format!("{}?", self.name) };
+        if self.description.is_empty() {
+            name
+        } else {
+            format!("{}: {}", name, self.description)
+        }
+    }
+}
+
+/// Marker line introducing the example output block in a command file.
+const OUTPUT_MARKER: &str = "## output";
+/// Marker line introducing the argument descriptions in a command file.
+const ARGS_MARKER: &str = "## args";
+/// Marker line introducing `key: value` metadata (author, created).
+const META_MARKER: &str = "## meta";
+/// First-line prefix of a redirect left behind by `mv --alias`.
+pub const ALIAS_PREFIX: &str = "@alias ";
+
 impl Command {
     pub fn new(path: impl Into<String>, command: impl Into<String>, explanation: impl Into<String>) -> Self {
         Self {
             path: path.into(),
             command: command.into(),
             explanation: explanation.into(),
+            output: String::new(),
+            args: Vec::new(),
+            author: String::new(),
+            created: String::new(),
+            cwd: String::new(),
+            shell_args: String::new(),
+            order: None,
+            expires: String::new(),
+            alias: String::new(),
         }
     }
 
+    /// A redirect from `path` to the command now at `target`.
+    pub fn alias_to(path: &str, target: &str) -> Self {
+        let mut cmd = Self::new(path, format!("{}{}", ALIAS_PREFIX, target), format!("Moved to {}", target));
+        cmd.alias = target.to_string();
+        cmd
+    }
+
     pub fn from_file(path: &str, file_path: &Path) -> Result<Self> {
         let content = fs::read_to_string(file_path)?;
         Self::parse(path, &content, file_path)
     }
 
-    fn parse(path: &str, content: &str, file_path: &Path) -> Result<Self> {
-        let lines: Vec<&str> = content.lines().collect();
+    /// Parse file content; `file_path` is only used in errors.
+    pub fn parse(path: &str, content: &str, file_path: &Path) -> Result<Self> {
+        // Tolerate files saved by Windows editors: a UTF-8 BOM and CRLF endings
+        let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+        let lines: Vec<&str> = content.lines().map(|l| l.trim_end_matches('\r')).collect();
 
         if lines.is_empty() {
             return Err(CmdxError::InvalidFormat(file_path.to_path_buf()));
         }
 
         let command = lines[0].trim().to_string();
-        let explanation = lines.get(1).map(|s| s.trim()).unwrap_or("").to_string();
+        // Compact files omit an empty explanation, so line 2 may open a section
+        let explanation = lines
+            .get(1)
+            .map(|s| s.trim())
+            .filter(|s| !is_marker(s))
+            .unwrap_or("")
+            .to_string();
 
         if command.is_empty() {
             return Err(CmdxError::InvalidFormat(file_path.to_path_buf()));
         }
 
+        let output = section(&lines, OUTPUT_MARKER).join("\n").trim_end().to_string();
+        let args = section(&lines, ARGS_MARKER)
+            .iter()
+            .filter_map(|l| ArgSpec::parse(l))
+            .collect();
+
+        let mut author = String::new();
+        let mut created = String::new();
+        let mut cwd = String::new();
+        let mut shell_args = String::new();
+        let mut order = None;
+        let mut expires = String::new();
+        let alias = command
+            .strip_prefix(ALIAS_PREFIX)
+            .map(|target| target.trim().to_string())
+            .unwrap_or_default();
+        for line in section(&lines, META_MARKER) {
+            match line.split_once(':') {
+                Some(("author", value)) => author = value.trim().to_string(),
+                Some(("created", value)) => created = value.trim().to_string(),
+                Some(("cwd", value)) => cwd = value.trim().to_string(),
+                Some(("shell_args", value)) => shell_args = value.trim().to_string(),
+                Some(("order", value)) => order = value.trim().parse().ok(),
+                Some(("expires", value)) => expires = value.trim().to_string(),
+                _ => {}
+            }
+        }
+
         Ok(Self {
             path: path.to_string(),
             command,
             explanation,
+            output,
+            args,
+            author,
+            created,
+            cwd,
+            shell_args,
+            order,
+            expires,
+            alias,
         })
     }
 
+    /// Whether the command matches one of the [`DANGEROUS_PATTERNS`].
+    pub fn is_dangerous(&self) -> bool {
+        let normalized = self
+            .command
+            .to_lowercase()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        DANGEROUS_PATTERNS.iter().any(|p| normalized.contains(p))
+    }
+
+    /// Whether `expires` lies at or before `now` (unix seconds). Commands
+    /// without a readable expiry never expire.
+    pub fn is_expired(&self, now: u64) -> bool {
+        parse_timestamp(&self.expires).is_some_and(|at| at <= now)
+    }
+
+    /// Fail with [`CmdxError::CommandTooLarge`] if the command, explanation
+    /// and output together exceed `limit` bytes. A limit of 0 disables the check.
+    pub fn check_size(&self, limit: usize) -> Result<()> {
+        let size = self.command.len() + self.explanation.len() + self.output.len();
+        if limit > 0 && size > limit {
+            return Err(CmdxError::CommandTooLarge { path: self.path.clone(), size, limit });
+        }
+        Ok(())
+    }
+
+    /// Description for a placeholder, if the `## args` block has one.
+    pub fn arg(&self, name: &str) -> Option<&ArgSpec> {
+        self.args.iter().find(|a| a.name == name)
+    }
+
+    /// Substitute placeholder values; placeholders without a value are kept.
+    pub fn fill(&self, values: &[(String, String)]) -> String {
+        split_placeholders(&self.command)
+            .into_iter()
+            .map(|(text, is_placeholder)| {
+                if !is_placeholder {
+                    return text;
+                }
+                let name = &text[1..text.len() - 1];
+                values
+                    .iter()
+                    .find(|(n, _)| n == name)
+                    .map_or(text, |(_, value)| value.as_str())
+            })
+            .collect()
+    }
+
+    /// Names of `{placeholder}` parameters in the command, in order of first use.
+    pub fn placeholders(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = Vec::new();
+        for (text, is_placeholder) in split_placeholders(&self.command) {
+            if !is_placeholder {
+                continue;
+            }
+            let name = &text[1..text.len() - 1];
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        names
+    }
+
     pub fn to_file_content(&self) -> String {
-        format!("{}\n{}\n", self.command, self.explanation)
+        self.to_file_content_with(false)
+    }
+
+    /// File content; `compact` drops the explanation line when it is empty
+    /// (store format v2).
+    pub fn to_file_content_with(&self, compact: bool) -> String {
+        let mut content = if compact && self.explanation.is_empty() {
+            format!("{}\n", self.command)
+        } else {
+            format!("{}\n{}\n", self.command, self.explanation)
+        };
+        let has_meta = !self.author.is_empty()
+            || !self.created.is_empty()
+            || !self.cwd.is_empty()
+            || !self.shell_args.is_empty()
+            || self.order.is_some()
+            || !self.expires.is_empty();
+        if has_meta {
+            content.push_str(META_MARKER);
+            content.push('\n');
+            if !self.author.is_empty() {
+                content.push_str(&format!("author: {}\n", self.author));
+            }
+            if !self.created.is_empty() {
+                content.push_str(&format!("created: {}\n", self.created));
+            }
+            if !self.cwd.is_empty() {
+                content.push_str(&format!("cwd: {}\n", self.cwd));
+            }
+            if !self.shell_args.is_empty() {
+                content.push_str(&format!("shell_args: {}\n", self.shell_args));
+            }
+            if let Some(order) = self.order {
+                content.push_str(&format!("order: {}\n", order));
+            }
+            if !self.expires.is_empty() {
+                content.push_str(&format!("expires: {}\n", self.expires));
+            }
+        }
+        if !self.args.is_empty() {
+            content.push_str(ARGS_MARKER);
+            content.push('\n');
+            for arg in &self.args {
+                content.push_str(&arg.to_line());
+                content.push('\n');
+            }
+        }
+        if !self.output.is_empty() {
+            content.push_str(&format!("{}\n{}\n", OUTPUT_MARKER, self.output));
+        }
+        content
     }
 }
 
+fn is_marker(line: &str) -> bool {
+    [OUTPUT_MARKER, ARGS_MARKER, META_MARKER].contains(&line)
+}
+
+/// Lines following `marker`, up to the next known section marker.
+fn section<'a>(lines: &[&'a str], marker: &str) -> Vec<&'a str> {
+    // Output is free text and always last: never look for markers inside it
+    let output_start = lines.iter().position(|l| l.trim() == OUTPUT_MARKER);
+    if marker == OUTPUT_MARKER {
+        return output_start.map(|i| lines[i + 1..].to_vec()).unwrap_or_default();
+    }
+
+    let head = &lines[..output_start.unwrap_or(lines.len())];
+    let Some(start) = head.iter().position(|l| l.trim() == marker) else {
+        return vec![];
+    };
+
+    head[start + 1..]
+        .iter()
+        .take_while(|l| !is_marker(l.trim()))
+        .copied()
+        .collect()
+}
+
+/// Split text into segments, flagging `{name}` placeholders.
+///
+/// Names are limited to letters, digits, `_` and `-`, and `${VAR}` is left
+/// alone, so shell syntax like `${HOME}` or `awk '{print $1}'` is not
+/// mistaken for a placeholder.
+pub fn split_placeholders(text: &str) -> Vec<(&str, bool)> {
+    let mut segments = Vec::new();
+    let mut start = 0;
+    let bytes = text.as_bytes();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'{' && (i == 0 || bytes[i - 1] != b'$') {
+            if let Some(len) = text[i + 1..].find('}') {
+                let name = &text[i + 1..i + 1 + len];
+                let valid = !name.is_empty()
+                    && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+                if valid {
+                    if start < i {
+                        segments.push((&text[start..i], false));
+                    }
+                    let end = i + len + 2;
+                    segments.push((&text[i..end], true));
+                    start = end;
+                    i = end;
+                    continue;
+                }
+            }
+        }
+        i += 1;
+    }
+
+    if start < text.len() {
+        segments.push((&text[start..], false));
+    }
+    segments
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::path::PathBuf;
 
+    #[test]
+    fn test_is_dangerous() {
+        assert!(Command::new("a", "sudo rm  -rf /tmp/x", "").is_dangerous());
+        assert!(Command::new("a", "git push --force origin main", "").is_dangerous());
+        assert!(Command::new("a", "docker system prune -af", "").is_dangerous());
+        assert!(!Command::new("a", "git push origin main", "").is_dangerous());
+        assert!(!Command::new("a", "ls -la", "").is_dangerous());
+    }
+
     #[test]
     fn test_parse_valid() {
         let content = "docker system prune -af\nRemove all containers";
@@ -63,6 +412,97 @@ mod tests {
         assert_eq!(cmd.explanation, "Remove all containers");
     }
 
+    #[test]
+    fn test_parse_args_block() {
+        let content = "curl localhost:{port}/{route}\nHit a route\n## args\nport: the service port\nroute?\n## output\nok\n";
+        let cmd = Command::parse("http/get", content, &PathBuf::from("test")).unwrap();
+        assert_eq!(cmd.args.len(), 2);
+        assert_eq!(cmd.arg("port").unwrap().description, "the service port");
+        assert!(!cmd.arg("route").unwrap().required);
+        assert_eq!(cmd.output, "ok");
+        assert_eq!(cmd.to_file_content(), content);
+    }
+
+    #[test]
+    fn test_parse_meta_block() {
+        let content = "make deploy\nShip it\n## meta\nauthor: alice\ncreated: 2026-01-02 03:04:05\ncwd: ~/src/app\nshell_args: -lc\norder: 2\n## args\nenv: target\n";
+        let cmd = Command::parse("ops/deploy", content, &PathBuf::from("test")).unwrap();
+        assert_eq!(cmd.author, "alice");
+        assert_eq!(cmd.created, "2026-01-02 03:04:05");
+        assert_eq!(cmd.cwd, "~/src/app");
+        assert_eq!(cmd.shell_args, "-lc");
+        assert_eq!(cmd.order, Some(2));
+        assert_eq!(cmd.args.len(), 1);
+        assert_eq!(cmd.to_file_content(), content);
+    }
+
+    #[test]
+    fn test_alias_round_trip() {
+        let alias = Command::alias_to("old/path", "new/path");
+        let parsed = Command::parse("old/path", &alias.to_file_content(), &PathBuf::from("test")).unwrap();
+        assert_eq!(parsed.alias, "new/path");
+        assert!(Command::new("a", "ls", "").alias.is_empty());
+    }
+
+    #[test]
+    fn test_compact_round_trip() {
+        let cmd = Command::new("fs/ls", "ls -la", "");
+        assert_eq!(cmd.to_file_content_with(true), "ls -la\n");
+        assert_eq!(cmd.to_file_content_with(false), "ls -la\n\n");
+
+        let mut with_meta = cmd.clone();
+        with_meta.author = "alice".to_string();
+        let content = with_meta.to_file_content_with(true);
+        assert_eq!(content, "ls -la\n## meta\nauthor: alice\n");
+
+        let parsed = Command::parse("fs/ls", &content, &PathBuf::from("test")).unwrap();
+        assert_eq!(parsed.explanation, "");
+        assert_eq!(parsed.author, "alice");
+        assert_eq!(parsed.to_file_content_with(true), content);
+    }
+
+    #[test]
+    fn test_fill_placeholders() {
+        let cmd = Command::new("a", "ssh {user}@{host} -p {port}", "");
+        let values = vec![("user".to_string(), "root".to_string()), ("host".to_string(), "db".to_string())];
+        assert_eq!(cmd.fill(&values), "ssh root@db -p {port}");
+    }
+
+    #[test]
+    fn test_parse_crlf() {
+        let content = "git status\r\nShow status\r\n## output\r\nclean\r\n";
+        let cmd = Command::parse("git/status", content, &PathBuf::from("test")).unwrap();
+        assert_eq!(cmd.command, "git status");
+        assert_eq!(cmd.explanation, "Show status");
+        assert_eq!(cmd.output, "clean");
+    }
+
+    #[test]
+    fn test_parse_bom() {
+        let content = "\u{feff}ls -la\nList files\n";
+        let cmd = Command::parse("fs/ls", content, &PathBuf::from("test")).unwrap();
+        assert_eq!(cmd.command, "ls -la");
+        assert_eq!(cmd.explanation, "List files");
+    }
+
+    #[test]
+    fn test_parse_output_block() {
+        let content = "git branch\nList branches\n## output\n* main\n  feature\n";
+        let cmd = Command::parse("git/branch", content, &PathBuf::from("test")).unwrap();
+        assert_eq!(cmd.explanation, "List branches");
+        assert_eq!(cmd.output, "* main\n  feature");
+        assert_eq!(cmd.to_file_content(), content);
+    }
+
+    #[test]
+    fn test_placeholders() {
+        let cmd = Command::new("k8s/logs", "kubectl logs -n {namespace} {pod} {pod} | awk '{print $1}' ${HOME}", "");
+        assert_eq!(cmd.placeholders(), vec!["namespace", "pod"]);
+
+        let segments = split_placeholders("ssh {host} -p 22");
+        assert_eq!(segments, vec![("ssh ", false), ("{host}", true), (" -p 22", false)]);
+    }
+
     #[test]
     fn test_parse_no_explanation() {
         let content = "git status";
@@ -70,4 +510,17 @@ mod tests {
         assert_eq!(cmd.command, "git status");
         assert_eq!(cmd.explanation, "");
     }
+
+    #[test]
+    fn test_check_size() {
+        let mut cmd = Command::new("big/cat", "x".repeat(10), "");
+        assert!(cmd.check_size(10).is_ok());
+        assert!(cmd.check_size(0).is_ok());
+
+        cmd.output = "y".repeat(5);
+        assert!(matches!(
+            cmd.check_size(10),
+            Err(CmdxError::CommandTooLarge { size: 15, limit: 10, .. })
+        ));
+    }
 }
@@ -1,51 +1,544 @@
 use crate::command::Command;
 use crate::config::Config;
 use crate::error::{CmdxError, Result};
-use crate::store::Store;
+use crate::history::{format_timestamp, now, parse_timestamp};
+use crate::log::{esuccess, success, verbose};
+use crate::store::{self, validate_path, CommandStore};
 use colored::Colorize;
-use std::io::{self, Write};
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::process::{Command as Process, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
 
-pub fn exec(path: String, command: Option<String>, explain: Option<String>, force: bool) -> Result<()> {
+use super::export::parse_duration;
+use super::templates::find_template;
+
+/// Flags for `cmdx add` beyond the path, command and explanation.
+#[derive(Debug, Default)]
+pub struct AddOptions {
+    pub force: bool,
+    pub from: Option<String>,
+    pub no_duplicates: bool,
+    pub skip_duplicate_check: bool,
+    pub auto_path: bool,
+    pub dry_run: bool,
+    pub auto_explain: bool,
+    pub replace_newlines: bool,
+    pub after: Option<String>,
+    pub expires: Option<String>,
+}
+
+pub fn exec(
+    path: String,
+    command: Option<String>,
+    explain: Option<String>,
+    opts: AddOptions,
+) -> Result<()> {
     let config = Config::load()?;
-    let store = Store::new(&config);
+    let store = store::open_filesystem(&config)?;
 
     if !store.exists() {
         return Err(CmdxError::NotInitialized);
     }
 
+    // With --auto-path the single positional argument is the command itself
+    let (path, command) = match command {
+        None if opts.auto_path => {
+            let suggested = suggest_path(&path);
+            let input = prompt(&format!("Path [{}]: ", suggested))?;
+            (if input.is_empty() { suggested } else { input }, Some(path))
+        }
+        command => (path, command),
+    };
+
     // Validate path
-    if path.is_empty() || path.starts_with('/') || path.contains("..") {
-        return Err(CmdxError::InvalidPath(path));
-    }
+    validate_path(&path)?;
+    let expires = opts.expires.as_deref().map(|when| expiry(when, now())).transpose()?;
+
+    // Seed from a built-in template if requested
+    let (command, explain) = match opts.from {
+        Some(name) => {
+            let template = find_template(&name)?;
+            (Some(template.command), explain.or(Some(template.explanation)))
+        }
+        None => (command, explain),
+    };
 
     // Get command (prompt if not provided)
     let cmd_text = match command {
+        Some(c) if opts.replace_newlines => join_lines(&c, &config.core.newline_join),
         Some(c) => c,
-        None => prompt("Command: ")?,
+        None => prompt_command(&config.core.newline_join)?,
     };
 
     if cmd_text.is_empty() {
         return Err(CmdxError::InvalidPath("Command cannot be empty".to_string()));
     }
 
-    // Get explanation (prompt if not provided)
+    // Line 2 of the file is the explanation, so a newline would split the command
+    if cmd_text.contains(['\n', '\r']) {
+        return Err(CmdxError::InvalidPath(
+            "Command spans multiple lines; pass --replace-newlines to join them".to_string(),
+        ));
+    }
+
+    // Get explanation (prompt if not provided, offering the tool's own
+    // description with --auto-explain, else the configured template)
     let explanation = match explain {
         Some(e) => e,
-        None => prompt("Explanation: ")?,
+        None => {
+            let template = opts
+                .auto_explain
+                .then(|| help_summary(&cmd_text))
+                .flatten()
+                .unwrap_or_else(|| expand_template(&config.core.default_explanation));
+            if template.is_empty() {
+                prompt("Explanation: ")?
+            } else {
+                let input = prompt(&format!("Explanation [{}]: ", template))?;
+                if input.is_empty() { template } else { input }
+            }
+        }
     };
 
-    let cmd = Command::new(&path, cmd_text, explanation);
-    store.add(&cmd, force)?;
+    let mut cmd = Command::new(&path, cmd_text, explanation);
+    cmd.author = config.author();
+    cmd.created = format_timestamp(now());
+    cmd.expires = expires.unwrap_or_default();
+    cmd.check_size(config.core.max_command_bytes)?;
+
+    // Joined input must read back as the same command
+    let parsed = Command::parse(&path, &store.file_content(&cmd)?, &store.command_path(&path))?;
+    if parsed.command != cmd.command {
+        return Err(CmdxError::InvalidFormat(store.command_path(&path)));
+    }
+
+    if !opts.skip_duplicate_check {
+        if let Some(existing) = find_duplicate(&store, &cmd)? {
+            if opts.no_duplicates {
+                return Err(CmdxError::AlreadyExists(store.command_path(&existing)));
+            }
+            eprintln!(
+                "{} Same command already stored at {}",
+                "!".yellow(),
+                existing.cyan()
+            );
+        }
+    }
 
-    println!("{} Added {}", "✓".green(), path.cyan());
+    // Siblings are renumbered too, but only written once the add goes ahead
+    let renumbered = match &opts.after {
+        Some(after) => {
+            let (siblings, order) = place_after(&store.list(category(&path))?, after, &path)?;
+            cmd.order = Some(order);
+            siblings
+        }
+        None => Vec::new(),
+    };
+
+    if opts.dry_run {
+        let file_path = store.command_path(&cmd.path);
+        if file_path.exists() && !opts.force {
+            return Err(CmdxError::AlreadyExists(file_path));
+        }
+        // Path on stderr so stdout is exactly the file content
+        eprintln!("{} {}", "Would write:".dimmed(), file_path.display());
+        print!("{}", store.file_content(&cmd)?);
+        return Ok(());
+    }
+
+    store.add(&cmd, opts.force)?;
+    for sibling in &renumbered {
+        store.add(sibling, true)?;
+    }
+
+    esuccess!("Added {}", path.cyan());
     Ok(())
 }
 
+/// The category holding `path`, `None` at the top level.
+fn category(path: &str) -> Option<&str> {
+    path.rsplit_once('/').map(|(dir, _)| dir)
+}
+
+/// Number `path`'s siblings in their current manual order with `path`
+/// inserted after `after`. Returns the siblings whose order changed and the
+/// new command's order.
+fn place_after(listed: &[Command], after: &str, path: &str) -> Result<(Vec<Command>, u32)> {
+    if category(after) != category(path) {
+        return Err(CmdxError::InvalidPath(format!(
+            "--after {} is not in the same category as {}",
+            after, path
+        )));
+    }
+
+    // Only direct children; an overwritten command is placed afresh
+    let mut siblings: Vec<&Command> = listed
+        .iter()
+        .filter(|c| category(&c.path) == category(path) && c.path != path)
+        .collect();
+    siblings.sort_by_key(|c| (c.order.is_none(), c.order, c.path.clone()));
+
+    let at = siblings
+        .iter()
+        .position(|c| c.path == after)
+        .ok_or_else(|| CmdxError::NotFound(after.to_string()))?
+        + 1;
+
+    let mut changed = Vec::new();
+    for (i, sibling) in siblings.iter().enumerate() {
+        let index = i as u32 + if i < at { 1 } else { 2 };
+        if sibling.order != Some(index) {
+            changed.push(Command { order: Some(index), ..(*sibling).clone() });
+        }
+    }
+    Ok((changed, at as u32 + 1))
+}
+
+/// Resolve `--expires` to the timestamp stored in `## meta`: either a
+/// duration from `now` (`7d`) or a date (`2026-11-01`, optionally with time).
+fn expiry(when: &str, now: u64) -> Result<String> {
+    if let Ok(duration) = parse_duration(when) {
+        return Ok(format_timestamp(now + duration.as_secs()));
+    }
+    parse_timestamp(when).map(format_timestamp).ok_or_else(|| {
+        CmdxError::Config(format!(
+            "Invalid expiry '{}' (use a duration like 7d or a date like 2026-11-01)",
+            when
+        ))
+    })
+}
+
+/// `cmdx add --batch`: add one command per `path<TAB>command<TAB>explanation`
+/// line, reporting each line like `import` does.
+pub fn batch(file: String, force: bool) -> Result<()> {
+    let config = Config::load()?;
+    let store = store::open_filesystem(&config)?;
+
+    if !store.exists() {
+        return Err(CmdxError::NotInitialized);
+    }
+
+    let content = if file == "-" {
+        let mut buffer = String::new();
+        io::stdin().read_to_string(&mut buffer)?;
+        buffer
+    } else {
+        fs::read_to_string(&file)?
+    };
+
+    let author = config.author();
+    let created = format_timestamp(now());
+    let mut added = 0;
+    let mut skipped = 0;
+    let mut failed = 0;
+
+    for (i, line) in content.lines().enumerate() {
+        let line = line.trim_end_matches('\r');
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+
+        let mut cmd = match parse_batch_line(line) {
+            Some(cmd) => cmd,
+            None => {
+                eprintln!("{} line {}: expected path<TAB>command[<TAB>explanation]", "!".red(), i + 1);
+                failed += 1;
+                continue;
+            }
+        };
+        cmd.author = author.clone();
+        cmd.created = created.clone();
+
+        match cmd.check_size(config.core.max_command_bytes).and_then(|()| store.add(&cmd, force)) {
+            Ok(()) => {
+                println!("{} {}", "+".green(), cmd.path);
+                added += 1;
+            }
+            Err(CmdxError::AlreadyExists(_)) => {
+                println!("{} {} (exists)", "~".yellow(), cmd.path);
+                skipped += 1;
+            }
+            Err(e) => {
+                eprintln!("{} line {}: {}: {}", "!".red(), i + 1, cmd.path, e);
+                failed += 1;
+            }
+        }
+    }
+
+    println!();
+    success!(
+        "Added {} commands{}{}",
+        added,
+        if skipped > 0 {
+            format!(", skipped {} (use --force to overwrite)", skipped)
+        } else {
+            String::new()
+        },
+        if failed > 0 { format!(", {} failed", failed) } else { String::new() }
+    );
+
+    Ok(())
+}
+
+/// Split a `--batch` line into a command; the explanation column is optional.
+fn parse_batch_line(line: &str) -> Option<Command> {
+    let mut fields = line.splitn(3, '\t').map(str::trim);
+    let path = fields.next().filter(|p| !p.is_empty())?;
+    let command = fields.next().filter(|c| !c.is_empty())?;
+    let explanation = fields.next().unwrap_or("");
+    Some(Command::new(path, command, explanation))
+}
+
+/// How long `--auto-explain` waits for `<tool> --help`.
+const HELP_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// First non-empty line of `<tool> --help`, best effort.
+fn help_summary(command: &str) -> Option<String> {
+    let tool = command_tool(command)?;
+    verbose!("running '{} --help' for --auto-explain", tool);
+
+    let mut child = Process::new(tool)
+        .arg("--help")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .ok()?;
+
+    // Read while the tool runs: long help would otherwise fill the pipe and
+    // block it until the timeout
+    let (tx, rx) = mpsc::channel();
+    let (stdout, stderr) = (child.stdout.take()?, child.stderr.take()?);
+    let tx_err = tx.clone();
+    thread::spawn(move || tx.send((true, first_line(stdout))));
+    thread::spawn(move || tx_err.send((false, first_line(stderr))));
+
+    // Some tools print help to stderr; stdout still wins when it has a line
+    let deadline = Instant::now() + HELP_TIMEOUT;
+    let mut stdout_done = false;
+    let mut stderr_line = None;
+    let summary = loop {
+        if stdout_done {
+            if let Some(line) = stderr_line.take() {
+                break line;
+            }
+        }
+        let left = deadline.saturating_duration_since(Instant::now());
+        match rx.recv_timeout(left) {
+            Ok((true, Some(line))) => break Some(line),
+            Ok((true, None)) => stdout_done = true,
+            Ok((false, line)) => stderr_line = Some(line),
+            Err(_) => {
+                verbose!("'{} --help' timed out", tool);
+                break None;
+            }
+        }
+    };
+
+    let _ = child.kill();
+    let _ = child.wait();
+    summary
+}
+
+/// First non-empty line read from `pipe`, trimmed.
+fn first_line(pipe: impl Read) -> Option<String> {
+    let mut reader = BufReader::new(pipe);
+    let mut buf = Vec::new();
+    loop {
+        buf.clear();
+        match reader.read_until(b'\n', &mut buf) {
+            Ok(0) | Err(_) => return None,
+            Ok(_) => {
+                let line = String::from_utf8_lossy(&buf);
+                if !line.trim().is_empty() {
+                    return Some(line.trim().to_string());
+                }
+            }
+        }
+    }
+}
+
+/// The program a command line invokes, skipping `VAR=value` and `sudo`.
+fn command_tool(command: &str) -> Option<&str> {
+    command
+        .split_whitespace()
+        .find(|t| !t.contains('=') && *t != "sudo")
+}
+
+/// Path of another stored command with identical command text, if any.
+fn find_duplicate(store: &dyn CommandStore, cmd: &Command) -> Result<Option<String>> {
+    Ok(store
+        .list(None)?
+        .into_iter()
+        .find(|c| c.path != cmd.path && c.command == cmd.command)
+        .map(|c| c.path))
+}
+
+/// Suggest a store path from a command line, e.g.
+/// `docker system prune -af` -> `docker/system-prune`.
+fn suggest_path(command: &str) -> String {
+    let mut tokens = command
+        .split_whitespace()
+        .skip_while(|t| t.contains('=') || *t == "sudo");
+
+    let tool = tokens
+        .next()
+        .map(|t| t.rsplit('/').next().unwrap_or(t))
+        .unwrap_or("cmd");
+
+    let sub: Vec<&str> = tokens
+        .take_while(|t| {
+            !t.starts_with('-') && t.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        })
+        .take(2)
+        .collect();
+
+    if sub.is_empty() {
+        format!("{}/default", tool)
+    } else {
+        format!("{}/{}", tool, sub.join("-"))
+    }
+}
+
+/// Expand `{date}` in the explanation template to today's date (UTC).
+fn expand_template(template: &str) -> String {
+    if !template.contains("{date}") {
+        return template.to_string();
+    }
+
+    let date = format_timestamp(now());
+    template.replace("{date}", &date[..10])
+}
+
+/// Read the command at the prompt. A line ending in `\` continues on the
+/// next one, as in a shell, and the lines are joined per `newline_join`.
+fn prompt_command(style: &str) -> Result<String> {
+    let mut lines = vec![prompt("Command: ")?];
+    while lines.last().is_some_and(|l| l.ends_with('\\')) {
+        lines.push(prompt("> ")?);
+    }
+    Ok(join_lines(&lines.join("\n"), style))
+}
+
+/// Join a multi-line command into one line.
+///
+/// `semicolon` separates lines with `; ` unless a line already ends in an
+/// operator (`&&`, `|`, `;`, ...); `continuation` joins them with a space,
+/// which is what a trailing `\` means to the shell. Either way, trailing
+/// `\` are dropped and blank lines skipped.
+fn join_lines(text: &str, style: &str) -> String {
+    let mut joined = String::new();
+    let mut separator = "";
+
+    for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        let (line, continued) = match line.strip_suffix('\\') {
+            Some(rest) => (rest.trim_end(), true),
+            None => (line, false),
+        };
+        joined.push_str(separator);
+        joined.push_str(line);
+
+        let last_word = line.rsplit(char::is_whitespace).next().unwrap_or("");
+        let open = ["&&", "||", "|", ";", "{", "("].iter().any(|op| line.ends_with(op))
+            || ["do", "then", "else"].contains(&last_word);
+        separator = if style == "semicolon" && !continued && !open { "; " } else { " " };
+    }
+    joined
+}
+
+/// Prompts go to stderr so `add --dry-run > file` captures only the file.
 fn prompt(msg: &str) -> Result<String> {
-    print!("{}", msg);
-    io::stdout().flush()?;
+    eprint!("{}", msg);
+    io::stderr().flush()?;
 
     let mut input = String::new();
     io::stdin().read_line(&mut input)?;
     Ok(input.trim().to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expiry() {
+        let now = 1_792_195_200; // 2026-10-17 00:00:00
+        assert_eq!(expiry("7d", now).unwrap(), "2026-10-24 00:00:00");
+        assert_eq!(expiry("2026-11-01", now).unwrap(), "2026-11-01 00:00:00");
+        assert_eq!(expiry("2026-11-01 09:30:00", now).unwrap(), "2026-11-01 09:30:00");
+        assert!(expiry("next week", now).is_err());
+    }
+
+    #[test]
+    fn test_place_after_renumbers_category() {
+        let listed = vec![
+            Command::new("db/setup", "make setup", ""),
+            Command::new("db/teardown", "make down", ""),
+            Command::new("db/sub/x", "true", ""),
+        ];
+        let (changed, order) = place_after(&listed, "db/setup", "db/migrate").unwrap();
+        assert_eq!(order, 2);
+        let orders: Vec<_> = changed.iter().map(|c| (c.path.as_str(), c.order)).collect();
+        assert_eq!(orders, vec![("db/setup", Some(1)), ("db/teardown", Some(3))]);
+
+        assert!(place_after(&listed, "web/setup", "db/migrate").is_err());
+        assert!(place_after(&listed, "db/nope", "db/migrate").is_err());
+    }
+
+    #[test]
+    fn test_parse_batch_line() {
+        let cmd = parse_batch_line("git/st\tgit status -sb\tShort status").unwrap();
+        assert_eq!((cmd.path.as_str(), cmd.command.as_str()), ("git/st", "git status -sb"));
+        assert_eq!(cmd.explanation, "Short status");
+
+        let cmd = parse_batch_line("fs/ls\tls -la").unwrap();
+        assert_eq!(cmd.explanation, "");
+
+        assert!(parse_batch_line("only-a-path").is_none());
+        assert!(parse_batch_line("path\t ").is_none());
+    }
+
+    #[test]
+    fn test_join_lines() {
+        let text = "cd /tmp \\\n  && ls\nfor f in *; do\n  echo $f\ndone\n\n";
+        assert_eq!(join_lines(text, "semicolon"), "cd /tmp && ls; for f in *; do echo $f; done");
+        assert_eq!(join_lines("make \\\n  build\nmake test", "continuation"), "make build make test");
+        assert_eq!(join_lines("ls -la", "semicolon"), "ls -la");
+        assert_eq!(join_lines("echo undo\necho redo", "semicolon"), "echo undo; echo redo");
+    }
+
+    #[test]
+    fn test_suggest_path() {
+        assert_eq!(suggest_path("docker system prune -af"), "docker/system-prune");
+        assert_eq!(suggest_path("sudo systemctl restart nginx"), "systemctl/restart-nginx");
+        assert_eq!(suggest_path("RUST_LOG=debug cargo run --release"), "cargo/run");
+        assert_eq!(suggest_path("/usr/bin/ls -la"), "ls/default");
+        assert_eq!(suggest_path("git log --oneline"), "git/log");
+    }
+
+    #[test]
+    fn test_find_duplicate() {
+        let store = store::MemoryStore::default();
+        store.add(&Command::new("git/st", "git status", ""), false).unwrap();
+
+        let same = Command::new("git/status", "git status", "");
+        assert_eq!(find_duplicate(&store, &same).unwrap().as_deref(), Some("git/st"));
+        assert_eq!(find_duplicate(&store, &Command::new("git/st", "git status", "")).unwrap(), None);
+    }
+
+    #[test]
+    fn test_first_line() {
+        assert_eq!(first_line(&b"\n   \n  usage: rg [OPTIONS]\nmore\n"[..]), Some("usage: rg [OPTIONS]".to_string()));
+        assert_eq!(first_line(&b"\n \n"[..]), None);
+    }
+
+    #[test]
+    fn test_command_tool() {
+        assert_eq!(command_tool("sudo RUST_LOG=debug cargo run"), Some("cargo"));
+        assert_eq!(command_tool("rg -n TODO"), Some("rg"));
+        assert_eq!(command_tool("FOO=1"), None);
+    }
+}
@@ -0,0 +1,152 @@
+use crate::command::Command;
+use crate::config::Config;
+use crate::error::{CmdxError, Result};
+use crate::log::success;
+use crate::store::{self, CommandStore};
+use colored::Colorize;
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use super::edit::{editor, open_in_editor};
+use super::empty::print_empty_store;
+
+const HEADER: &str = "\
+# One 'path: explanation' per line; edit the explanations and save.
+# Deleting a line leaves that command alone, unknown paths are skipped
+# and lines starting with '#' are ignored.
+";
+
+/// `cmdx annotate`: edit the explanations of every command under `prefix`
+/// in one editor buffer.
+pub fn exec(prefix: Option<String>) -> Result<()> {
+    let config = Config::load()?;
+    let store = store::open(&config)?;
+
+    if !store.exists() {
+        return Err(CmdxError::NotInitialized);
+    }
+
+    let commands = store.list(prefix.as_deref())?;
+    if commands.is_empty() {
+        match &prefix {
+            Some(p) => println!("{}", format!("No commands under {}.", p).dimmed()),
+            None => print_empty_store(),
+        }
+        return Ok(());
+    }
+
+    let buffer = create_buffer(&render(&commands))?;
+
+    println!("{} Opening {} explanations in {}", "→".dimmed(), commands.len(), editor());
+    let edited = open_in_editor(&buffer).and_then(|()| Ok(fs::read_to_string(&buffer)?));
+    fs::remove_file(&buffer)?;
+
+    let updated = apply(store.as_ref(), &commands, &parse(&edited?))?;
+    if updated == 0 {
+        println!("{}", "No explanations changed.".dimmed());
+    } else {
+        success!("Updated {} explanation{}", updated, if updated == 1 { "" } else { "s" });
+    }
+    Ok(())
+}
+
+/// Write `content` to a new private file in the config directory. Never
+/// reuses an existing path, so a planted file or symlink is never written through.
+fn create_buffer(content: &str) -> Result<PathBuf> {
+    let dir = Config::config_dir();
+    fs::create_dir_all(&dir)?;
+
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    for attempt in 0.. {
+        let path = dir.join(format!("annotate-{}-{}.txt", std::process::id(), attempt));
+        match options.open(&path) {
+            Ok(mut file) => {
+                file.write_all(content.as_bytes())?;
+                return Ok(path);
+            }
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+    unreachable!("ran out of buffer names")
+}
+
+fn render(commands: &[Command]) -> String {
+    let mut out = String::from(HEADER);
+    for cmd in commands {
+        out.push_str(&format!("{}: {}\n", cmd.path, cmd.explanation));
+    }
+    out
+}
+
+/// `(path, explanation)` for each non-comment line of the edited buffer.
+fn parse(content: &str) -> Vec<(String, String)> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once(':'))
+        .map(|(path, explanation)| (path.trim().to_string(), explanation.trim().to_string()))
+        .collect()
+}
+
+/// Save each changed explanation, matching lines to `commands` by path so
+/// order doesn't matter. Returns how many commands were rewritten.
+fn apply(store: &dyn CommandStore, commands: &[Command], edits: &[(String, String)]) -> Result<usize> {
+    let by_path: HashMap<&str, &Command> = commands.iter().map(|c| (c.path.as_str(), c)).collect();
+
+    // A path listed twice keeps its last explanation
+    let mut changed: HashMap<&str, &str> = HashMap::new();
+    for (path, explanation) in edits {
+        match by_path.get(path.as_str()) {
+            Some(cmd) if cmd.explanation != *explanation => {
+                changed.insert(cmd.path.as_str(), explanation);
+            }
+            Some(cmd) => {
+                changed.remove(cmd.path.as_str());
+            }
+            None => eprintln!("{} Skipping unknown path {}", "!".yellow(), path.cyan()),
+        }
+    }
+
+    for cmd in commands {
+        if let Some(explanation) = changed.get(cmd.path.as_str()) {
+            let updated = Command { explanation: explanation.to_string(), ..cmd.clone() };
+            store.add(&updated, true)?;
+        }
+    }
+    Ok(changed.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_matches_by_path() {
+        let store = store::MemoryStore::default();
+        let commands = vec![
+            Command::new("git/log", "git log", "Show history"),
+            Command::new("git/st", "git status", ""),
+            Command::new("k8s/pods", "kubectl get pods", "List pods"),
+        ];
+        for cmd in &commands {
+            store.add(cmd, false).unwrap();
+        }
+
+        // Reordered, one line dropped, one unknown path, comments kept
+        let edited = "# header\nk8s/pods: List pods\ngit/nope: ?\n\ngit/st: Short status: porcelain\n";
+        let updated = apply(&store, &commands, &parse(edited)).unwrap();
+
+        assert_eq!(updated, 1);
+        assert_eq!(store.get("git/st").unwrap().explanation, "Short status: porcelain");
+        assert_eq!(store.get("git/log").unwrap().explanation, "Show history");
+        assert_eq!(parse(&render(&commands)).len(), 3);
+    }
+}
@@ -0,0 +1,129 @@
+use crate::command::Command;
+use crate::error::Result;
+use crate::history;
+use colored::Colorize;
+use std::io::{self, IsTerminal, Write};
+
+use super::confirm::read_answer;
+
+/// Ask for a value for each `{placeholder}` and return the filled-in command.
+///
+/// Prompts show the `## args` description when there is one. Without a
+/// terminal the command is returned unchanged, so scripts see the template.
+pub fn prompt_args(cmd: &Command) -> Result<Command> {
+    let names = cmd.placeholders();
+    if names.is_empty() || !io::stdin().is_terminal() {
+        return Ok(cmd.clone());
+    }
+
+    let mut values = Vec::new();
+    for name in names {
+        let spec = cmd.arg(name);
+        let required = spec.is_none_or(|a| a.required);
+        let label = match spec {
+            Some(a) => {
+                let kind = if a.required { "required" } else { "optional" };
+                if a.description.is_empty() {
+                    format!("{} ({})", name, kind)
+                } else {
+                    format!("{} ({}): {}", name, kind, a.description.dimmed())
+                }
+            }
+            None => name.to_string(),
+        };
+
+        let value = loop {
+            eprint!("{} > ", label);
+            io::stderr().flush()?;
+
+            let mut input = String::new();
+            io::stdin().read_line(&mut input)?;
+            let input = input.trim().to_string();
+
+            if !input.is_empty() || !required {
+                break input;
+            }
+            eprintln!("{} {} is required", "!".yellow(), name.cyan());
+        };
+        values.push((name.to_string(), value));
+    }
+
+    Ok(Command {
+        command: cmd.fill(&values),
+        ..cmd.clone()
+    })
+}
+
+/// `run --interactive-args`: list every placeholder first, then ask for each
+/// with last time's value as the default, and confirm the filled command
+/// before it runs. Values are remembered per command for the next run.
+pub fn prompt_args_remembered(cmd: &Command) -> Result<Option<Command>> {
+    let names = cmd.placeholders();
+    if names.is_empty() || !io::stdin().is_terminal() {
+        return Ok(Some(cmd.clone()));
+    }
+
+    let mut defaults = history::remembered_args(&cmd.path);
+
+    eprintln!("{} {}", cmd.path.cyan(), format!("({} arguments)", names.len()).dimmed());
+    for name in &names {
+        let mut line = format!("  {}", name.cyan());
+        if let Some(spec) = cmd.arg(name).filter(|a| !a.description.is_empty()) {
+            line.push_str(&format!(" {}", spec.description.dimmed()));
+        }
+        if let Some(last) = defaults.get(*name) {
+            line.push_str(&format!(" {}", format!("[{}]", last).dimmed()));
+        }
+        eprintln!("{}", line);
+    }
+
+    loop {
+        let mut values = Vec::new();
+        for name in &names {
+            let required = cmd.arg(name).is_none_or(|a| a.required);
+            let default = defaults.get(*name).cloned().unwrap_or_default();
+
+            let value = loop {
+                if default.is_empty() {
+                    eprint!("{} > ", name);
+                } else {
+                    eprint!("{} [{}] > ", name, default);
+                }
+                io::stderr().flush()?;
+
+                let mut input = String::new();
+                if io::stdin().read_line(&mut input)? == 0 {
+                    return Ok(None);
+                }
+                let input = match input.trim() {
+                    "" => default.clone(),
+                    typed => typed.to_string(),
+                };
+
+                if !input.is_empty() || !required {
+                    break input;
+                }
+                eprintln!("{} {} is required", "!".yellow(), name.cyan());
+            };
+            values.push((name.to_string(), value));
+        }
+
+        let filled = cmd.fill(&values);
+        eprint!("{} {} {} ", "→".dimmed(), filled.white().bold(), "Use these values? [Y/n/q]".dimmed());
+        io::stderr().flush()?;
+
+        // End of input quits rather than accepting the values
+        let Some(answer) = read_answer()? else {
+            return Ok(None);
+        };
+        match answer.trim().to_lowercase().as_str() {
+            "" | "y" | "yes" => {
+                history::remember_args(&cmd.path, &values)?;
+                return Ok(Some(Command { command: filled, ..cmd.clone() }));
+            }
+            "q" | "quit" => return Ok(None),
+            // Go round again with this attempt's answers as the defaults
+            _ => defaults = values.into_iter().collect(),
+        }
+    }
+}
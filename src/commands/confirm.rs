@@ -0,0 +1,83 @@
+use crate::command::Command;
+use crate::error::Result;
+use colored::Colorize;
+use std::io::{self, Write};
+
+/// Maximum number of affected commands listed before truncating.
+const PREVIEW_LIMIT: usize = 20;
+
+/// Print the commands a bulk operation will touch and ask for confirmation.
+/// Both go to stderr, so piped output (`find --exec`, `export`) stays clean.
+///
+/// Callers skip this entirely when `--force` is given.
+pub fn confirm_affected(commands: &[Command], action: &str, default_yes: bool) -> Result<bool> {
+    eprintln!(
+        "{} {} command{} will be affected:",
+        "!".yellow(),
+        commands.len(),
+        if commands.len() == 1 { "" } else { "s" }
+    );
+
+    for cmd in commands.iter().take(PREVIEW_LIMIT) {
+        eprintln!("  {} {}", cmd.path.cyan(), cmd.command.dimmed());
+    }
+
+    if commands.len() > PREVIEW_LIMIT {
+        eprintln!(
+            "  {}",
+            format!("...and {} more", commands.len() - PREVIEW_LIMIT).dimmed()
+        );
+    }
+
+    eprint!("{}? {} ", action, choices(default_yes));
+    io::stderr().flush()?;
+
+    read_yes(default_yes)
+}
+
+/// Read one answer line from stdin, or `None` at end of input.
+pub fn read_answer() -> Result<Option<String>> {
+    let mut input = String::new();
+    if io::stdin().read_line(&mut input)? == 0 {
+        return Ok(None);
+    }
+    Ok(Some(input))
+}
+
+/// Read a y/n answer from stdin. End of input (stdin closed or redirected,
+/// as under cron or CI) is a no, whatever `default_yes` says.
+pub fn read_yes(default_yes: bool) -> Result<bool> {
+    Ok(read_answer()?.is_some_and(|input| is_yes(&input, default_yes)))
+}
+
+/// Prompt suffix showing which answer Enter picks (`core.confirm_default`).
+pub fn choices(default_yes: bool) -> &'static str {
+    if default_yes {
+        "[Y/n]"
+    } else {
+        "[y/N]"
+    }
+}
+
+/// Interpret a y/n answer; an empty answer takes the default.
+pub fn is_yes(input: &str, default_yes: bool) -> bool {
+    match input.trim().to_ascii_lowercase().as_str() {
+        "" => default_yes,
+        "y" | "yes" => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_answer_takes_default() {
+        assert!(!is_yes("\n", false));
+        assert!(is_yes("\n", true));
+        assert!(is_yes("Y\n", false));
+        assert!(!is_yes("n\n", true));
+        assert!(!is_yes("maybe", true));
+    }
+}
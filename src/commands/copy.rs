@@ -1,14 +1,22 @@
+use crate::command::Command as StoredCommand;
 use crate::config::Config;
 use crate::error::{CmdxError, Result};
-use crate::store::Store;
-use crate::commands::find::best_match;
+use crate::history;
+use crate::log::{esuccess, verbose};
+use crate::store;
+use crate::commands::args::prompt_args;
+use crate::commands::empty::print_empty_store;
+use crate::commands::find::resolve_interactive;
 use colored::Colorize;
+use std::env;
 use std::io::Write;
+use std::path::Path;
 use std::process::{Command, Stdio};
+use std::sync::Once;
 
-pub fn exec(query: String) -> Result<()> {
+pub fn exec(query: String, no_prompt: bool) -> Result<()> {
     let config = Config::load()?;
-    let store = Store::new(&config);
+    let store = store::open(&config)?;
 
     if !store.exists() {
         return Err(CmdxError::NotInitialized);
@@ -16,38 +24,157 @@ pub fn exec(query: String) -> Result<()> {
 
     // Try exact match first, then fuzzy
     let cmd = match store.get(&query) {
-        Ok(c) => c,
+        Ok(c) => {
+            verbose!("exact path match: {}", c.path);
+            c
+        }
         Err(_) => {
+            verbose!("no exact path '{}', falling back to fuzzy match", query);
             let commands = store.list(None)?;
-            best_match(&query, &commands)
-                .cloned()
-                .ok_or_else(|| CmdxError::NotFound(query.clone()))?
+            if commands.is_empty() {
+                print_empty_store();
+                return Err(CmdxError::NotFound(query));
+            }
+            match resolve_interactive(&query, &commands, !no_prompt)? {
+                Some(c) => c.clone(),
+                None => {
+                    eprintln!("{}", "Cancelled.".dimmed());
+                    return Ok(());
+                }
+            }
         }
     };
 
+    copy_command(&cmd, &config)
+}
+
+/// `cmdx last`: copy the most recently copied command again, or just print
+/// it with `print_only`.
+pub fn last(print_only: bool) -> Result<()> {
+    let config = Config::load()?;
+    let store = store::open(&config)?;
+
+    if !store.exists() {
+        return Err(CmdxError::NotInitialized);
+    }
+
+    let path = history::last_copied().ok_or(CmdxError::NothingCopied)?;
+    let cmd = match store.get(&path) {
+        Ok(cmd) => cmd,
+        Err(CmdxError::NotFound(_)) => {
+            eprintln!("{} {} was copied last but has since been removed or moved", "!".yellow(), path.cyan());
+            return Err(CmdxError::NotFound(path));
+        }
+        Err(e) => return Err(e),
+    };
+
+    if print_only {
+        eprintln!("{}", cmd.path.cyan());
+        println!("{}", config.display_command(&cmd.command));
+        return Ok(());
+    }
+    copy_command(&cmd, &config)
+}
+
+/// Copy an already-resolved command, falling back to printing it.
+///
+/// `{placeholders}` are filled in first when at a terminal.
+pub fn copy_command(cmd: &StoredCommand, config: &Config) -> Result<()> {
+    let cmd = &prompt_args(cmd)?;
+    let text = config.display_command(&cmd.command);
+
     // Try clipboard, fallback to bat/cat
-    if copy_to_clipboard(&cmd.command, &config.clipboard.tool) {
-        println!("{} Copied: {}", "✓".green(), cmd.path.cyan());
+    remember(&cmd.path);
+    if copy_to_clipboard(&text, &config.clipboard.tool) {
+        esuccess!("Copied: {}", cmd.path.cyan());
+        if !cmd.cwd.is_empty() {
+            eprintln!("  {} {}", "cwd:".dimmed(), cmd.cwd);
+        }
     } else {
-        // Clipboard failed, print with bat or plain
-        print_with_bat(&cmd.command, &cmd.path, &cmd.explanation);
+        // Clipboard failed, print via highlighter chain or plain
+        print_fallback(&text, &cmd.path, &cmd.explanation, &config.display.highlighters);
     }
-
     Ok(())
 }
 
+/// Record `path` for `cmdx last`; failing to is never worth an error.
+pub fn remember(path: &str) {
+    if let Err(e) = history::remember_copied(path) {
+        verbose!("failed to record last copied: {}", e);
+    }
+}
+
+/// Clipboard tools cmdx knows how to drive, in auto-detect order.
+pub const CLIPBOARD_TOOLS: [&str; 3] = ["wl-copy", "xclip", "xsel"];
+
+static MISSING_TOOL_HINT: Once = Once::new();
+
 pub fn copy_to_clipboard(text: &str, tool: &str) -> bool {
+    let ok = try_clipboard(text, tool);
+
+    // Falling back to printing is confusing when the user expected a copy
+    if !ok && available_clipboard_tools().is_empty() {
+        MISSING_TOOL_HINT.call_once(|| {
+            eprintln!(
+                "{} No clipboard tool found; install wl-copy, xclip or xsel, or set [clipboard] tool",
+                "hint:".yellow()
+            );
+        });
+    }
+    ok
+}
+
+/// Known clipboard tools present on `$PATH`.
+pub fn available_clipboard_tools() -> Vec<&'static str> {
+    CLIPBOARD_TOOLS
+        .into_iter()
+        .filter(|tool| on_path(tool))
+        .collect()
+}
+
+/// `which`-style lookup of an executable on `$PATH`.
+fn on_path(program: &str) -> bool {
+    let Some(paths) = env::var_os("PATH") else {
+        return false;
+    };
+    env::split_paths(&paths).any(|dir| {
+        let candidate = dir.join(program);
+        candidate.is_file() && is_executable(&candidate)
+    })
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata().map(|m| m.permissions().mode() & 0o111 != 0).unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(_path: &Path) -> bool {
+    true
+}
+
+fn try_clipboard(text: &str, tool: &str) -> bool {
+    verbose!("clipboard tool setting: {}", tool);
+    verbose!("clipboard tools on PATH: {:?}", available_clipboard_tools());
     match tool {
-        "wl-copy" => try_wl_copy(text),
-        "xclip" => try_xclip(text),
-        "xsel" => try_xsel(text),
-        "auto" | _ => {
+        "wl-copy" => traced("wl-copy", try_wl_copy(text)),
+        "xclip" => traced("xclip", try_xclip(text)),
+        "xsel" => traced("xsel", try_xsel(text)),
+        _ => {
             // Auto-detect: try wl-copy -> xclip -> xsel
-            try_wl_copy(text) || try_xclip(text) || try_xsel(text)
+            traced("wl-copy", try_wl_copy(text))
+                || traced("xclip", try_xclip(text))
+                || traced("xsel", try_xsel(text))
         }
     }
 }
 
+fn traced(tool: &str, ok: bool) -> bool {
+    verbose!("{}: {}", tool, if ok { "ok" } else { "failed or not installed" });
+    ok
+}
+
 fn try_wl_copy(text: &str) -> bool {
     if let Ok(mut child) = Command::new("wl-copy")
         .stdin(Stdio::piped())
@@ -98,12 +225,32 @@ fn try_xsel(text: &str) -> bool {
     false
 }
 
-fn print_with_bat(command: &str, path: &str, explanation: &str) {
-    println!("{}", path.cyan());
-    
-    // Try bat first
-    let bat_result = Command::new("bat")
-        .args(["--style=plain", "--language=bash", "--paging=never"])
+fn print_fallback(command: &str, path: &str, explanation: &str, highlighters: &[String]) {
+    eprintln!("{}", path.cyan());
+
+    // Try each configured highlighter in order, then plain print
+    let printed = highlighters
+        .iter()
+        .any(|tool| print_with_highlighter(tool, command));
+
+    if !printed {
+        println!("{}", command);
+    }
+
+    if !explanation.is_empty() {
+        eprintln!("{} {}", "→".dimmed(), explanation.dimmed());
+    }
+}
+
+fn print_with_highlighter(tool: &str, command: &str) -> bool {
+    let args: &[&str] = match tool {
+        "bat" => &["--style=plain", "--language=bash", "--paging=never"],
+        "glow" => &["-"],
+        _ => &[],
+    };
+
+    let result = Command::new(tool)
+        .args(args)
         .stdin(Stdio::piped())
         .spawn()
         .and_then(|mut child| {
@@ -113,12 +260,17 @@ fn print_with_bat(command: &str, path: &str, explanation: &str) {
             child.wait()
         });
 
-    if bat_result.is_err() {
-        // Fallback to plain print
-        println!("{}", command);
-    }
-    
-    if !explanation.is_empty() {
-        println!("{} {}", "→".dimmed(), explanation.dimmed());
+    verbose!("highlighter {}: {}", tool, if result.is_ok() { "ok" } else { "unavailable" });
+    result.map(|s| s.success()).unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_on_path() {
+        assert!(on_path("sh"));
+        assert!(!on_path("cmdx-no-such-tool"));
     }
 }
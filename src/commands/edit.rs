@@ -1,30 +1,55 @@
+use crate::command::Command;
 use crate::config::Config;
 use crate::error::{CmdxError, Result};
-use crate::store::Store;
+use crate::log::success;
+use crate::store::{self, CommandStore};
+use crate::tui;
 use colored::Colorize;
 use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::process::Command as Process;
 
-pub fn exec(path: String) -> Result<()> {
+use super::find::fuzzy_search;
+use super::empty::print_empty_store;
+
+pub fn exec(query: String) -> Result<()> {
     let config = Config::load()?;
-    let store = Store::new(&config);
+    let store = store::open_filesystem(&config)?;
 
     if !store.exists() {
         return Err(CmdxError::NotInitialized);
     }
 
-    // Verify command exists
-    let _ = store.get(&path)?;
+    let path = match resolve_path(&store, &query)? {
+        Some(path) => path,
+        None => {
+            println!("{}", "Cancelled.".dimmed());
+            return Ok(());
+        }
+    };
     let file_path = store.command_path(&path);
 
-    let editor = env::var("EDITOR")
-        .or_else(|_| env::var("VISUAL"))
-        .unwrap_or_else(|_| "vi".to_string());
+    println!("{} Opening {} in {}", "→".dimmed(), path.cyan(), editor());
+
+    let backup = backup_path(&Config::config_dir(), &path);
+    edit_with_backup(&path, &file_path, &backup, open_in_editor)?;
 
-    println!("{} Opening {} in {}", "→".dimmed(), path.cyan(), editor);
+    success!("Updated {}", path.cyan());
+    Ok(())
+}
 
-    let status = Process::new(&editor)
-        .arg(&file_path)
+/// `$EDITOR`, else `$VISUAL`, else `vi`.
+pub fn editor() -> String {
+    env::var("EDITOR")
+        .or_else(|_| env::var("VISUAL"))
+        .unwrap_or_else(|_| "vi".to_string())
+}
+
+/// Open `file` in [`editor`] and wait for it to exit successfully.
+pub fn open_in_editor(file: &Path) -> Result<()> {
+    let status = Process::new(editor())
+        .arg(file)
         .status()
         .map_err(|e| CmdxError::Execution(e.to_string()))?;
 
@@ -32,6 +57,103 @@ pub fn exec(path: String) -> Result<()> {
         return Err(CmdxError::Execution("Editor exited with error".to_string()));
     }
 
-    println!("{} Updated {}", "✓".green(), path.cyan());
     Ok(())
 }
+
+/// An exact path, else the only fuzzy match, else the one picked in the TUI
+/// (`None` if the picker is cancelled).
+fn resolve_path(store: &dyn CommandStore, query: &str) -> Result<Option<String>> {
+    if store.get_entry(query).is_ok() {
+        return Ok(Some(query.to_string()));
+    }
+
+    let commands = store.list(None)?;
+    if commands.is_empty() {
+        print_empty_store();
+        return Err(CmdxError::NotFound(query.to_string()));
+    }
+    let matches: Vec<Command> = fuzzy_search(query, &commands)
+        .into_iter()
+        .map(|(cmd, _)| cmd.clone())
+        .collect();
+
+    match matches.len() {
+        0 => Err(CmdxError::NotFound(query.to_string())),
+        1 => Ok(Some(matches[0].path.clone())),
+        _ => Ok(tui::run(matches, query)?.map(|(cmd, _)| cmd.path)),
+    }
+}
+
+/// Run `edit` on the command file, keeping a copy at `backup` until the
+/// result parses. On any failure the original content is restored.
+fn edit_with_backup<F>(path: &str, file_path: &Path, backup: &Path, edit: F) -> Result<()>
+where
+    F: FnOnce(&Path) -> Result<()>,
+{
+    if let Some(parent) = backup.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::copy(file_path, backup)?;
+
+    let result = edit(file_path).and_then(|_| Command::from_file(path, file_path).map(|_| ()));
+
+    if result.is_err() {
+        fs::copy(backup, file_path)?;
+    }
+    fs::remove_file(backup)?;
+
+    result
+}
+
+/// Where `edit` keeps the original of `path` under `dir`. Outside the store,
+/// so a backup left behind by a killed cmdx never shows up as a command.
+fn backup_path(dir: &Path, path: &str) -> PathBuf {
+    dir.join("edit-backups").join(format!("{}.bak", path.replace('/', "%")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_file(name: &str, content: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("cmdx-edit-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("cmd");
+        fs::write(&file, content).unwrap();
+        file
+    }
+
+    #[test]
+    fn test_invalid_edit_restores_backup() {
+        let file = temp_file("invalid", "git status\nShow status\n");
+        let backup = backup_path(file.parent().unwrap(), "git/status");
+
+        let result = edit_with_backup("git/status", &file, &backup, |f| {
+            fs::write(f, "")?;
+            Ok(())
+        });
+
+        assert!(matches!(result, Err(CmdxError::InvalidFormat(_))));
+        assert_eq!(fs::read_to_string(&file).unwrap(), "git status\nShow status\n");
+        assert!(!backup.exists());
+        fs::remove_dir_all(file.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_valid_edit_removes_backup() {
+        let file = temp_file("valid", "git status\n");
+        let backup = backup_path(file.parent().unwrap(), "git/status");
+
+        edit_with_backup("git/status", &file, &backup, |f| {
+            // The backup is kept apart from the command file
+            assert!(backup.exists() && backup.parent() != f.parent());
+            fs::write(f, "git status -sb\nShort status\n")?;
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(fs::read_to_string(&file).unwrap(), "git status -sb\nShort status\n");
+        assert!(!backup.exists());
+        fs::remove_dir_all(file.parent().unwrap()).unwrap();
+    }
+}
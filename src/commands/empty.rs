@@ -0,0 +1,11 @@
+use colored::Colorize;
+
+/// Shown wherever a command finds nothing because the store has no commands
+/// yet, so a new user sees the same next step everywhere.
+pub fn print_empty_store() {
+    eprintln!(
+        "{} Add a command with {}",
+        "Your store is empty.".dimmed(),
+        "cmdx add <path> \"<command>\"".cyan()
+    );
+}
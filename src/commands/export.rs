@@ -1,37 +1,89 @@
 use crate::command::Command;
 use crate::config::Config;
 use crate::error::{CmdxError, Result};
-use crate::store::Store;
+use crate::log::{esuccess, success};
+use crate::store::{self, CommandStore};
 use colored::Colorize;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::io::{self, Write};
 use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use super::confirm::confirm_affected;
+use super::empty::print_empty_store;
+
+/// Newest export format understood by import. Version 2 adds `config`.
+pub const EXPORT_VERSION: u32 = 2;
 
 #[derive(Serialize, Deserialize)]
-struct ExportData {
-    version: u32,
-    commands: Vec<Command>,
+pub struct ExportData {
+    pub version: u32,
+    pub commands: Vec<Command>,
+    /// Raw config.toml contents, present only with `--include-config`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config: Option<String>,
 }
 
-pub fn exec(output: Option<String>) -> Result<()> {
+pub fn exec(
+    output: Option<String>,
+    dir: Option<String>,
+    filter: Option<String>,
+    since: Option<String>,
+    include_config: bool,
+    force: bool,
+) -> Result<()> {
     let config = Config::load()?;
-    let store = Store::new(&config);
+    let store = store::open_filesystem(&config)?;
 
     if !store.exists() {
         return Err(CmdxError::NotInitialized);
     }
 
-    let commands = store.list(None)?;
+    let narrowed = filter.is_some() || since.is_some();
+    let mut commands = store.list(filter.as_deref())?;
+
+    if let Some(since) = since {
+        let cutoff = SystemTime::now()
+            .checked_sub(parse_duration(&since)?)
+            .unwrap_or(SystemTime::UNIX_EPOCH);
+        commands.retain(|cmd| store.modified(&cmd.path).map(|t| t >= cutoff).unwrap_or(false));
+    }
 
     if commands.is_empty() {
-        println!("{} No commands to export", "!".yellow());
+        if narrowed {
+            eprintln!("{} No commands to export", "!".yellow());
+        } else {
+            print_empty_store();
+        }
+        return Ok(());
+    }
+
+    // A whole-store export needs no preview; a narrowed one may not be what was meant
+    if narrowed && !force && !confirm_affected(&commands, "Export", config.core.confirm_default)? {
+        eprintln!("{}", "Cancelled.".dimmed());
         return Ok(());
     }
 
+    if let Some(dir) = dir {
+        return export_to_dir(&commands, Path::new(&dir));
+    }
+
+    let config_content = if include_config {
+        let path = Config::config_path();
+        if !path.exists() {
+            return Err(CmdxError::Config(format!("No config file at {}", path.display())));
+        }
+        Some(fs::read_to_string(path)?)
+    } else {
+        None
+    };
+
+    // Stay at version 1 unless the new field is used, so older cmdx can import
     let export_data = ExportData {
-        version: 1,
+        version: if config_content.is_some() { EXPORT_VERSION } else { 1 },
         commands,
+        config: config_content,
     };
 
     let json = serde_json::to_string_pretty(&export_data)
@@ -42,12 +94,7 @@ pub fn exec(output: Option<String>) -> Result<()> {
             // Write to file
             let path = Path::new(&path);
             fs::write(path, &json)?;
-            println!(
-                "{} Exported {} commands to {}",
-                "✓".green(),
-                export_data.commands.len(),
-                path.display()
-            );
+            success!("Exported {} commands to {}", export_data.commands.len(), path.display());
         }
         None => {
             // Write to stdout
@@ -58,3 +105,52 @@ pub fn exec(output: Option<String>) -> Result<()> {
 
     Ok(())
 }
+
+/// Recreate the store hierarchy under `dir` using the plain two-line format.
+fn export_to_dir(commands: &[Command], dir: &Path) -> Result<()> {
+    for cmd in commands {
+        let file_path = dir.join(&cmd.path);
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&file_path, cmd.to_file_content())?;
+    }
+
+    esuccess!("Exported {} commands to {}", commands.len(), dir.display());
+    Ok(())
+}
+
+/// Parse durations like `30m`, `24h`, `7d` or `2w`.
+pub fn parse_duration(s: &str) -> Result<Duration> {
+    let invalid = || CmdxError::Config(format!("Invalid duration '{}' (use e.g. 30m, 24h, 7d)", s));
+
+    let s = s.trim();
+    let unit = s.chars().last().ok_or_else(invalid)?;
+    let value: u64 = s[..s.len() - unit.len_utf8()].parse().map_err(|_| invalid())?;
+
+    let secs = match unit {
+        's' => value,
+        'm' => value * 60,
+        'h' => value * 3_600,
+        'd' => value * 86_400,
+        'w' => value * 604_800,
+        _ => return Err(invalid()),
+    };
+
+    Ok(Duration::from_secs(secs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("30m").unwrap(), Duration::from_secs(1_800));
+        assert_eq!(parse_duration("24h").unwrap(), Duration::from_secs(86_400));
+        assert_eq!(parse_duration("7d").unwrap(), Duration::from_secs(604_800));
+        assert!(parse_duration("7").is_err());
+        assert!(parse_duration("d").is_err());
+        assert!(parse_duration("").is_err());
+    }
+}
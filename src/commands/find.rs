@@ -1,51 +1,415 @@
 use crate::config::Config;
 use crate::error::{CmdxError, Result};
-use crate::store::Store;
-use crate::command::Command;
+use crate::store;
+use crate::command::{split_placeholders, Command};
+use crate::pager;
+use crate::text::{format_explanation, output_width, terminal_width};
+use crate::tui::{self, PickAction};
+use crate::history;
+use crate::log::verbose;
 use colored::Colorize;
 use fuzzy_matcher::skim::SkimMatcherV2;
 use fuzzy_matcher::FuzzyMatcher;
+use std::collections::HashMap;
+use std::io::{self, IsTerminal, Write};
+use std::process::Command as Process;
 
-pub fn exec(query: String) -> Result<()> {
+use super::confirm::confirm_affected;
+use super::copy::copy_command;
+use super::empty::print_empty_store;
+use super::list::EXPIRED_MARK;
+use super::run::{config_shell_args, run_command, Retry};
+
+/// Path column width when the terminal width is unknown (piped output).
+const DEFAULT_PATH_COLUMN: usize = 20;
+
+/// `--exec` asks before running for more matches than this.
+const EXEC_CONFIRM_THRESHOLD: usize = 5;
+
+/// Notes (example output) scores are divided by this so a hit in a long
+/// output block never outranks a path or command match.
+const NOTES_WEIGHT: i64 = 4;
+
+/// Shorter queries match almost any large block of text, so notes are only
+/// searched from this many characters on unless `--field notes` is given.
+pub const NOTES_MIN_QUERY: usize = 3;
+
+/// Flags for `cmdx find` beyond the query.
+#[derive(Debug)]
+pub struct FindOptions {
+    pub run: bool,
+    pub copy: bool,
+    pub interactive: bool,
+    pub no_pager: bool,
+    pub exec_template: Option<String>,
+    pub force: bool,
+    pub field: String,
+}
+
+pub fn exec(query: String, opts: FindOptions) -> Result<()> {
+    let FindOptions { run, copy, interactive, no_pager, exec_template, force, field } = opts;
     let config = Config::load()?;
-    let store = Store::new(&config);
+    let store = store::open(&config)?;
 
     if !store.exists() {
         return Err(CmdxError::NotInitialized);
     }
 
     let commands = store.list(None)?;
-    let matches = fuzzy_search(&query, &commands);
+    if commands.is_empty() {
+        print_empty_store();
+        return Ok(());
+    }
+    let matches = ranked_search(&query, &commands, &history::last_used(), &field);
 
     if matches.is_empty() {
         println!("{} No matches for '{}'", "✗".red(), query);
         return Ok(());
     }
 
-    for (cmd, _score) in matches.iter().take(10) {
-        println!("{:<20} {}", cmd.path.cyan(), cmd.command.white());
+    if let Some(template) = exec_template {
+        let commands: Vec<Command> = matches.iter().map(|(cmd, _)| (*cmd).clone()).collect();
+        return exec_each(&template, &commands, &config, force);
+    }
+
+    if interactive {
+        // A single hit needs no picker
+        let selected = if matches.len() == 1 {
+            Some((matches[0].0.clone(), PickAction::Copy))
+        } else {
+            let results = matches.iter().map(|(cmd, _)| (*cmd).clone()).collect();
+            tui::run(results, &query)?
+        };
+
+        return match selected {
+            Some((cmd, PickAction::Run)) => run_command(&cmd, &config, false, false, &[], false, Retry::default()),
+            Some((cmd, PickAction::Copy)) => copy_command(&cmd, &config),
+            None => Ok(()),
+        };
+    }
+
+    if run || copy {
+        let cmd = match choose_match(&top_matches(&matches))? {
+            Some(cmd) => cmd,
+            None => {
+                eprintln!("{}", "Cancelled.".dimmed());
+                return Ok(());
+            }
+        };
+
+        if run {
+            return run_command(cmd, &config, true, false, &[], false, Retry::default());
+        }
+        return copy_command(cmd, &config);
+    }
+
+    let width = output_width(&config.display);
+    let shown = &matches[..matches.len().min(10)];
+    let column = path_column_width(shown.iter().map(|(cmd, _)| cmd.path.as_str()), terminal_width());
+    let mut out = String::new();
+    let now = history::now();
+    for (cmd, _score) in shown {
+        out.push_str(&format!("{:<column$} {}", cmd.path.cyan(), cmd.command.white()));
+        if cmd.is_expired(now) {
+            out.push_str(&format!(" {}", EXPIRED_MARK.dimmed()));
+        }
+        out.push('\n');
         if !cmd.explanation.is_empty() {
-            println!("{:<20} {} {}", "", "→".dimmed(), cmd.explanation.dimmed());
+            out.push_str(&format_explanation(&cmd.explanation, column + 1, width));
+        }
+    }
+    pager::page(&out, !no_pager);
+
+    Ok(())
+}
+
+/// Fit the path column to the longest path shown, but never more than 40%
+/// of the terminal so the command keeps some room. Piped output keeps the
+/// fixed default so scripts see a stable layout.
+fn path_column_width<'a>(paths: impl Iterator<Item = &'a str>, terminal: Option<usize>) -> usize {
+    let Some(terminal) = terminal else {
+        return DEFAULT_PATH_COLUMN;
+    };
+
+    let longest = paths.map(|p| p.chars().count()).max().unwrap_or(0);
+    longest.min(terminal * 2 / 5).max(1)
+}
+
+/// Run `template` through the configured shell for each match.
+///
+/// A template ending in ` +` runs once, with each placeholder expanded to the
+/// values of all matches, like `find -exec ... {} +`.
+fn exec_each(template: &str, commands: &[Command], config: &Config, force: bool) -> Result<()> {
+    if commands.len() > EXEC_CONFIRM_THRESHOLD
+        && !force
+        && !confirm_affected(commands, "Run for each", config.core.confirm_default)?
+    {
+        eprintln!("{}", "Cancelled.".dimmed());
+        return Ok(());
+    }
+
+    let lines: Vec<String> = match template.trim_end().strip_suffix(" +") {
+        Some(batch) => vec![fill_exec_template(batch, commands)],
+        None => commands
+            .iter()
+            .map(|cmd| fill_exec_template(template, std::slice::from_ref(cmd)))
+            .collect(),
+    };
+
+    // Same shell invocation as `cmdx run`
+    let shell_args = config_shell_args(config)?;
+    let mut failed = 0;
+    for line in &lines {
+        verbose!("exec: {}", line);
+        let status = Process::new(&config.core.shell)
+            .args(&shell_args)
+            .arg(line)
+            .envs(&config.run.env)
+            .status()
+            .map_err(|e| CmdxError::Execution(e.to_string()))?;
+        if !status.success() {
+            failed += 1;
         }
     }
 
+    if failed > 0 {
+        return Err(CmdxError::Execution(format!("{} of {} failed", failed, lines.len())));
+    }
     Ok(())
 }
 
+/// Substitute `{path}`, `{command}` and `{explanation}` with the shell-quoted
+/// values of `commands`, space-separated. Other placeholders are kept.
+fn fill_exec_template(template: &str, commands: &[Command]) -> String {
+    split_placeholders(template)
+        .into_iter()
+        .map(|(text, is_placeholder)| {
+            let field: fn(&Command) -> &str = match text {
+                "{path}" if is_placeholder => |c| &c.path,
+                "{command}" if is_placeholder => |c| &c.command,
+                "{explanation}" if is_placeholder => |c| &c.explanation,
+                _ => return text.to_string(),
+            };
+            commands
+                .iter()
+                .map(|c| shell_quote(field(c)))
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect()
+}
+
+/// Single-quote a value so the shell passes it through as one word.
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
 pub fn fuzzy_search<'a>(query: &str, commands: &'a [Command]) -> Vec<(&'a Command, i64)> {
+    ranked_search(query, commands, &history::last_used(), "all")
+}
+
+/// Score a command's notes (its example output) against `query`, scaled
+/// down by [`NOTES_WEIGHT`]. Returns `None` for empty notes or queries too
+/// short to be meaningful against a large block of text.
+pub fn notes_score(matcher: &SkimMatcherV2, cmd: &Command, query: &str) -> Option<i64> {
+    if cmd.output.is_empty() || query.chars().count() < NOTES_MIN_QUERY {
+        return None;
+    }
+    matcher.fuzzy_match(&cmd.output, query).map(|score| score / NOTES_WEIGHT)
+}
+
+/// Fuzzy match and rank commands.
+///
+/// Equal scores are broken by, in order: shorter path, most recently run
+/// (per `last_used`), then alphabetical path. This keeps the best match for
+/// `copy`/`run` stable across invocations.
+///
+/// `field` is a `find --field` value; "all" also matches notes, at a
+/// reduced weight.
+fn ranked_search<'a>(
+    query: &str,
+    commands: &'a [Command],
+    last_used: &HashMap<String, u64>,
+    field: &str,
+) -> Vec<(&'a Command, i64)> {
     let matcher = SkimMatcherV2::default();
     let mut matches: Vec<(&Command, i64)> = commands
         .iter()
         .filter_map(|cmd| {
-            let search_text = format!("{} {} {}", cmd.path, cmd.command, cmd.explanation);
-            matcher.fuzzy_match(&search_text, query).map(|score| (cmd, score))
+            let score = match field {
+                "path" => matcher.fuzzy_match(&cmd.path, query),
+                "command" => matcher.fuzzy_match(&cmd.command, query),
+                "notes" => matcher.fuzzy_match(&cmd.output, query),
+                _ => {
+                    let search_text = format!("{} {} {}", cmd.path, cmd.command, cmd.explanation);
+                    matcher
+                        .fuzzy_match(&search_text, query)
+                        .max(notes_score(&matcher, cmd, query))
+                }
+            };
+            score.map(|score| (cmd, score))
         })
         .collect();
 
-    matches.sort_by(|a, b| b.1.cmp(&a.1));
+    matches.sort_by(|(a, a_score), (b, b_score)| {
+        let recency = |c: &Command| last_used.get(&c.path).copied().unwrap_or(0);
+        b_score
+            .cmp(a_score)
+            .then_with(|| a.path.len().cmp(&b.path.len()))
+            .then_with(|| recency(b).cmp(&recency(a)))
+            .then_with(|| a.path.cmp(&b.path))
+    });
+
+    for (cmd, score) in matches.iter().take(5) {
+        verbose!("match '{}': {} (score {})", query, cmd.path, score);
+    }
+
     matches
 }
 
 pub fn best_match<'a>(query: &str, commands: &'a [Command]) -> Option<&'a Command> {
     fuzzy_search(query, commands).into_iter().next().map(|(cmd, _)| cmd)
 }
+
+/// Matches scoring within this percentage of the top score count as ambiguous.
+const AMBIGUOUS_SCORE_PERCENT: i64 = 80;
+
+/// Resolve a fuzzy query, asking the user to pick when several matches are
+/// close to the best score and stdin is a terminal.
+///
+/// Returns `Ok(None)` if the user cancels the selection.
+pub fn resolve_interactive<'a>(
+    query: &str,
+    commands: &'a [Command],
+    prompt: bool,
+) -> Result<Option<&'a Command>> {
+    let matches = fuzzy_search(query, commands);
+    let top = match matches.first() {
+        Some(&(_, score)) => score,
+        None => return Err(CmdxError::NotFound(query.to_string())),
+    };
+
+    if !prompt || !io::stdin().is_terminal() {
+        return Ok(Some(matches[0].0));
+    }
+
+    let candidates: Vec<&Command> = matches
+        .iter()
+        .take_while(|(_, score)| score * 100 >= top * AMBIGUOUS_SCORE_PERCENT)
+        .take(9)
+        .map(|(cmd, _)| *cmd)
+        .collect();
+
+    choose_match(&candidates)
+}
+
+/// All matches sharing the highest score (expects results sorted by score).
+fn top_matches<'a>(matches: &[(&'a Command, i64)]) -> Vec<&'a Command> {
+    let top = match matches.first() {
+        Some(&(_, score)) => score,
+        None => return vec![],
+    };
+
+    matches
+        .iter()
+        .take_while(|(_, score)| *score == top)
+        .map(|(cmd, _)| *cmd)
+        .collect()
+}
+
+/// Pick one of several equally good candidates, prompting when ambiguous.
+pub fn choose_match<'a>(candidates: &[&'a Command]) -> Result<Option<&'a Command>> {
+    if candidates.len() <= 1 {
+        return Ok(candidates.first().copied());
+    }
+
+    eprintln!("{} Multiple equally good matches:", "?".yellow());
+    for (i, cmd) in candidates.iter().enumerate() {
+        eprintln!("  {} {:<20} {}", format!("{})", i + 1).dimmed(), cmd.path.cyan(), cmd.command);
+    }
+    eprint!("Select [1-{}]: ", candidates.len());
+    io::stderr().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+
+    Ok(input
+        .trim()
+        .parse::<usize>()
+        .ok()
+        .and_then(|n| n.checked_sub(1))
+        .and_then(|i| candidates.get(i).copied()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_column_width() {
+        let paths = ["git/log", "docker/system/prune"];
+        assert_eq!(path_column_width(paths.into_iter(), None), DEFAULT_PATH_COLUMN);
+        assert_eq!(path_column_width(paths.into_iter(), Some(120)), 19);
+        assert_eq!(path_column_width(paths.into_iter(), Some(40)), 16);
+    }
+
+    #[test]
+    fn test_fill_exec_template() {
+        let commands = vec![
+            Command::new("a/one", "echo 'hi'", ""),
+            Command::new("a/two", "ls", ""),
+        ];
+        assert_eq!(
+            fill_exec_template("printf %s {path} {command} {other}", &commands[..1]),
+            r"printf %s 'a/one' 'echo '\''hi'\''' {other}"
+        );
+        assert_eq!(fill_exec_template("echo {path}", &commands), "echo 'a/one' 'a/two'");
+    }
+
+    #[test]
+    fn test_tie_break_prefers_shorter_path() {
+        let commands = vec![
+            Command::new("docker/system/prune", "prune", ""),
+            Command::new("docker/prune", "prune", ""),
+        ];
+        let matches = ranked_search("prune", &commands, &HashMap::new(), "all");
+        assert_eq!(matches[0].1, matches[1].1);
+        assert_eq!(matches[0].0.path, "docker/prune");
+    }
+
+    #[test]
+    fn test_tie_break_recency_then_alphabetical() {
+        let commands = vec![
+            Command::new("b/prune", "prune", ""),
+            Command::new("a/prune", "prune", ""),
+            Command::new("c/prune", "prune", ""),
+        ];
+
+        let matches = ranked_search("prune", &commands, &HashMap::new(), "all");
+        assert_eq!(matches[0].0.path, "a/prune");
+
+        let last_used = HashMap::from([("c/prune".to_string(), 100)]);
+        let matches = ranked_search("prune", &commands, &last_used, "all");
+        assert_eq!(matches[0].0.path, "c/prune");
+        assert_eq!(matches[1].0.path, "a/prune");
+    }
+
+    #[test]
+    fn test_notes_match_ranks_below_path_match() {
+        let mut noted = Command::new("net/ip", "ip addr", "");
+        noted.output = "inet 10.0.0.2/24 scope global eth0".to_string();
+        let commands = vec![noted, Command::new("net/eth0", "ethtool eth0", "")];
+
+        let matches = ranked_search("eth0", &commands, &HashMap::new(), "all");
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].0.path, "net/eth0");
+        assert!(matches[1].1 < matches[0].1);
+
+        let matches = ranked_search("eth0", &commands, &HashMap::new(), "notes");
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].0.path, "net/ip");
+
+        // Too short to search notes by default
+        assert!(ranked_search("sc", &commands[..1], &HashMap::new(), "all").is_empty());
+    }
+}
@@ -0,0 +1,48 @@
+use crate::config::Config;
+use crate::error::{CmdxError, Result};
+use crate::history::{self, format_timestamp};
+use crate::store;
+use crate::commands::find::best_match;
+use colored::Colorize;
+
+pub fn exec(query: String) -> Result<()> {
+    let config = Config::load()?;
+    let store = store::open(&config)?;
+
+    if !store.exists() {
+        return Err(CmdxError::NotInitialized);
+    }
+
+    // Resolve to a stored path; fall back to the raw query for removed commands
+    let path = match store.get(&query) {
+        Ok(c) => c.path,
+        Err(_) => {
+            let commands = store.list(None)?;
+            best_match(&query, &commands)
+                .map(|c| c.path.clone())
+                .unwrap_or(query)
+        }
+    };
+
+    let entries: Vec<_> = history::load()?
+        .into_iter()
+        .filter(|e| e.path == path)
+        .collect();
+
+    if entries.is_empty() {
+        println!("{} No history for {}", "!".yellow(), path.cyan());
+        return Ok(());
+    }
+
+    println!("{}", path.cyan());
+    for entry in &entries {
+        let status = if entry.exit_code == 0 {
+            format!("{:>4}", entry.exit_code).green()
+        } else {
+            format!("{:>4}", entry.exit_code).red()
+        };
+        println!("  {}  {}", format_timestamp(entry.timestamp).dimmed(), status);
+    }
+
+    Ok(())
+}
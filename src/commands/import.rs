@@ -1,23 +1,29 @@
 use crate::command::Command;
 use crate::config::Config;
 use crate::error::{CmdxError, Result};
-use crate::store::Store;
+use crate::formats;
+use crate::log::success;
+use crate::store::{self, validate_path, CommandStore};
 use colored::Colorize;
-use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::fs;
-use std::io::{self, Read};
+use std::io::{self, IsTerminal, Read, Write};
 
-#[derive(Serialize, Deserialize)]
-struct ExportData {
-    version: u32,
-    commands: Vec<Command>,
-}
+use super::confirm::{choices, read_yes};
+use super::export::{ExportData, EXPORT_VERSION};
 
-pub fn exec(input: Option<String>, force: bool) -> Result<()> {
+pub fn exec(
+    input: Option<String>,
+    force: bool,
+    include_config: bool,
+    validate_only: bool,
+    from: Option<String>,
+) -> Result<()> {
     let config = Config::load()?;
-    let store = Store::new(&config);
+    let store = store::open(&config)?;
 
-    if !store.exists() {
+    // Checking a file doesn't touch the store, so it needn't exist yet
+    if !store.exists() && !validate_only {
         return Err(CmdxError::NotInitialized);
     }
 
@@ -31,21 +37,69 @@ pub fn exec(input: Option<String>, force: bool) -> Result<()> {
         }
     };
 
+    if let Some(format) = from {
+        return import_foreign(store.as_ref(), &format, &json, force, config.core.max_command_bytes);
+    }
+
     let export_data: ExportData = serde_json::from_str(&json)
         .map_err(|e| CmdxError::Config(format!("Invalid JSON: {}", e)))?;
 
-    if export_data.version != 1 {
+    if export_data.version == 0 || export_data.version > EXPORT_VERSION {
         return Err(CmdxError::Config(format!(
             "Unsupported export version: {}",
             export_data.version
         )));
     }
 
+    if validate_only {
+        return validate(&export_data, config.core.max_command_bytes);
+    }
+
+    if include_config {
+        match &export_data.config {
+            Some(content) => import_config(content, force, config.core.confirm_default)?,
+            None => println!("{} Export contains no config", "!".yellow()),
+        }
+    }
+
+    add_all(store.as_ref(), export_data.commands, force, config.core.max_command_bytes)
+}
+
+/// `--from`: convert another tool's snippets, then add them like an export.
+fn import_foreign(
+    store: &dyn CommandStore,
+    format: &str,
+    content: &str,
+    force: bool,
+    max_bytes: usize,
+) -> Result<()> {
+    let conversion = formats::convert(format, content)?;
+
+    for problem in &conversion.unmapped {
+        eprintln!("{} {}", "!".yellow(), problem);
+    }
+    success!(
+        "Converted {} {} snippets{}",
+        conversion.commands.len(),
+        format,
+        if conversion.unmapped.is_empty() {
+            String::new()
+        } else {
+            format!(", {} could not be mapped", conversion.unmapped.len())
+        }
+    );
+
+    add_all(store, conversion.commands, force, max_bytes)
+}
+
+/// Add each command, reporting per line and summarizing at the end.
+/// Commands over `max_bytes` are reported and skipped.
+fn add_all(store: &dyn CommandStore, commands: Vec<Command>, force: bool, max_bytes: usize) -> Result<()> {
     let mut imported = 0;
     let mut skipped = 0;
 
-    for cmd in export_data.commands {
-        match store.add(&cmd, force) {
+    for cmd in commands {
+        match cmd.check_size(max_bytes).and_then(|()| store.add(&cmd, force)) {
             Ok(()) => {
                 println!("{} {}", "+".green(), cmd.path);
                 imported += 1;
@@ -61,9 +115,8 @@ pub fn exec(input: Option<String>, force: bool) -> Result<()> {
     }
 
     println!();
-    println!(
-        "{} Imported {} commands{}",
-        "✓".green(),
+    success!(
+        "Imported {} commands{}",
         imported,
         if skipped > 0 {
             format!(", skipped {} (use --force to overwrite)", skipped)
@@ -74,3 +127,129 @@ pub fn exec(input: Option<String>, force: bool) -> Result<()> {
 
     Ok(())
 }
+
+/// `--validate-only`: report entries `import` would reject, without writing.
+fn validate(export_data: &ExportData, max_bytes: usize) -> Result<()> {
+    let mut seen = HashSet::new();
+    let mut invalid = 0;
+
+    for cmd in &export_data.commands {
+        let problems = entry_problems(cmd, &mut seen, max_bytes);
+        if problems.is_empty() {
+            continue;
+        }
+        invalid += 1;
+        for problem in problems {
+            println!("{} {}: {}", "✗".red(), cmd.path, problem);
+        }
+    }
+
+    if let Some(content) = &export_data.config {
+        if let Err(e) = toml::from_str::<Config>(content) {
+            println!("{} config: {}", "✗".red(), e);
+            invalid += 1;
+        }
+    }
+
+    let valid = export_data.commands.len().saturating_sub(invalid);
+    println!(
+        "{} {} valid, {} invalid (export version {})",
+        if invalid == 0 { "✓".green() } else { "!".yellow() },
+        valid,
+        invalid,
+        export_data.version
+    );
+
+    if invalid > 0 {
+        return Err(CmdxError::ImportInvalid(invalid));
+    }
+    Ok(())
+}
+
+/// Why a single exported command can't be imported, if anything.
+fn entry_problems(cmd: &Command, seen: &mut HashSet<String>, max_bytes: usize) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    if let Err(e) = validate_path(&cmd.path) {
+        problems.push(e.to_string());
+    }
+    if cmd.command.trim().is_empty() {
+        problems.push("command is empty".to_string());
+    }
+    if let Err(e) = cmd.check_size(max_bytes) {
+        problems.push(e.to_string());
+    }
+    if !seen.insert(cmd.path.clone()) {
+        problems.push("duplicate path in export".to_string());
+    }
+
+    problems
+}
+
+/// Restore an exported config.toml, asking before replacing an existing one.
+fn import_config(content: &str, force: bool, default_yes: bool) -> Result<()> {
+    // Refuse to write something that wouldn't load
+    toml::from_str::<Config>(content).map_err(|e| CmdxError::Config(format!("Exported config is invalid: {}", e)))?;
+
+    let path = Config::config_path();
+
+    if path.exists() && !force {
+        // stdin may be carrying the JSON itself; only prompt at a terminal
+        if !io::stdin().is_terminal() {
+            println!("{} Config exists, not overwritten (use --force)", "~".yellow());
+            return Ok(());
+        }
+
+        print!("Overwrite {}? {} ", path.display(), choices(default_yes));
+        io::stdout().flush()?;
+
+        if !read_yes(default_yes)? {
+            println!("{} Config kept", "~".yellow());
+            return Ok(());
+        }
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, content)?;
+    success!("Config restored to {}", path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entry_problems() {
+        let mut seen = HashSet::new();
+        assert!(entry_problems(&Command::new("git/log", "git log", ""), &mut seen, 64).is_empty());
+        assert_eq!(entry_problems(&Command::new("git/log", "git log", ""), &mut seen, 64).len(), 1);
+        assert_eq!(entry_problems(&Command::new("../etc/passwd", " ", ""), &mut seen, 64).len(), 2);
+        assert_eq!(entry_problems(&Command::new(".git/config", "x", ""), &mut seen, 64).len(), 1);
+        assert_eq!(entry_problems(&Command::new("big/cmd", "x".repeat(65), ""), &mut seen, 64).len(), 1);
+    }
+
+    #[test]
+    fn test_add_all_skips_existing() {
+        let store = store::MemoryStore::default();
+        store.add(&Command::new("git/log", "git log", ""), false).unwrap();
+
+        let commands = vec![Command::new("git/log", "git log -p", ""), Command::new("git/st", "git status", "")];
+        add_all(&store, commands, false, 0).unwrap();
+
+        assert_eq!(store.get("git/log").unwrap().command, "git log");
+        assert_eq!(store.list(None).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_add_all_skips_oversized() {
+        let store = store::MemoryStore::default();
+        let commands = vec![Command::new("big/cat", "x".repeat(100), ""), Command::new("git/st", "git status", "")];
+        add_all(&store, commands, false, 64).unwrap();
+
+        assert!(store.get_entry("big/cat").is_err());
+        assert_eq!(store.list(None).unwrap().len(), 1);
+    }
+}
@@ -1,17 +1,21 @@
 use crate::config::Config;
 use crate::error::Result;
-use crate::store::Store;
-use colored::Colorize;
+use crate::log::success;
+use crate::store::{self, CommandStore};
+use std::io::{self, IsTerminal, Write};
+use std::path::PathBuf;
+
+use super::confirm::read_yes;
+
+/// Export file `init` offers to restore, relative to the config directory.
+const BACKUP_FILE: &str = "backup.json";
 
 pub fn exec() -> Result<()> {
     let config = Config::default();
-    let store = Store::new(&config);
+    let store = store::open_filesystem(&config)?;
 
     if store.exists() {
-        println!("{} Store already initialized at {}", 
-            "✓".green(), 
-            store.root().display()
-        );
+        success!("Store already initialized at {}", store.root().display());
         return Ok(());
     }
 
@@ -21,14 +25,26 @@ pub fn exec() -> Result<()> {
     // Create config file
     Config::save_default()?;
 
-    println!("{} Initialized cmdx store at {}", 
-        "✓".green(), 
-        store.root().display()
-    );
-    println!("{} Config created at {}", 
-        "✓".green(), 
-        Config::config_path().display()
-    );
+    success!("Initialized cmdx store at {}", store.root().display());
+    success!("Config created at {}", Config::config_path().display());
+
+    if let Some(backup) = backup_to_offer() {
+        print!("Found a backup at {}. Import it? [y/N] ", backup.display());
+        io::stdout().flush()?;
+
+        if read_yes(false)? {
+            super::import::exec(Some(backup.to_string_lossy().to_string()), false, false, false, None)?;
+        }
+    }
 
     Ok(())
 }
+
+/// The conventional backup, when it exists and someone is there to answer.
+fn backup_to_offer() -> Option<PathBuf> {
+    if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+        return None;
+    }
+    let path = Config::config_dir().join(BACKUP_FILE);
+    path.is_file().then_some(path)
+}
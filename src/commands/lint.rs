@@ -0,0 +1,142 @@
+use crate::command::Command;
+use crate::config::{Config, LintConfig};
+use crate::error::{CmdxError, Result};
+use crate::log::success;
+use crate::store;
+use colored::Colorize;
+
+use super::empty::print_empty_store;
+
+pub fn exec(fix: bool) -> Result<()> {
+    let config = Config::load()?;
+    let store = store::open(&config)?;
+
+    if !store.exists() {
+        return Err(CmdxError::NotInitialized);
+    }
+
+    let commands = store.list(None)?;
+    if commands.is_empty() {
+        print_empty_store();
+        return Ok(());
+    }
+
+    let mut issues = 0;
+    let mut fixed = 0;
+
+    for cmd in commands {
+        let problems = check(&cmd, &config.lint);
+        if problems.is_empty() {
+            continue;
+        }
+
+        let fixed_path = fixed_path(&cmd.path, &config.lint);
+        let renamed = fix
+            && fixed_path != cmd.path
+            && match store.rename(&cmd.path, &fixed_path) {
+                Ok(()) => true,
+                Err(e) => {
+                    eprintln!("{} {}: {}", "!".red(), cmd.path, e);
+                    false
+                }
+            };
+
+        for problem in &problems {
+            let fixable = problem.fixable && renamed;
+            if fixable {
+                fixed += 1;
+            } else {
+                issues += 1;
+            }
+            println!(
+                "{} {}: {}",
+                if fixable { "~".green() } else { "✗".red() },
+                cmd.path.cyan(),
+                problem.message
+            );
+        }
+
+        if renamed {
+            println!("  {} {}", "→".dimmed(), fixed_path.cyan());
+        }
+    }
+
+    if fixed > 0 {
+        success!("Fixed {} issue(s)", fixed);
+    }
+
+    if issues > 0 {
+        return Err(CmdxError::LintFailed(issues));
+    }
+
+    if fixed == 0 {
+        success!("No lint issues");
+    }
+    Ok(())
+}
+
+struct Problem {
+    message: String,
+    fixable: bool,
+}
+
+fn check(cmd: &Command, rules: &LintConfig) -> Vec<Problem> {
+    let mut problems = Vec::new();
+
+    if rules.lowercase_paths && cmd.path.chars().any(|c| c.is_uppercase()) {
+        problems.push(Problem { message: "path is not lowercase".to_string(), fixable: true });
+    }
+
+    if rules.no_spaces && cmd.path.contains(char::is_whitespace) {
+        problems.push(Problem { message: "path contains spaces".to_string(), fixable: true });
+    }
+
+    if rules.require_explanation && cmd.explanation.is_empty() {
+        problems.push(Problem { message: "missing explanation".to_string(), fixable: false });
+    }
+
+    if let Some(max) = rules.max_command_length {
+        let len = cmd.command.chars().count();
+        if len > max {
+            problems.push(Problem {
+                message: format!("command is {} chars (max {})", len, max),
+                fixable: false,
+            });
+        }
+    }
+
+    problems
+}
+
+/// Path with the auto-fixable rules applied.
+fn fixed_path(path: &str, rules: &LintConfig) -> String {
+    let mut path = path.to_string();
+    if rules.lowercase_paths {
+        path = path.to_lowercase();
+    }
+    if rules.no_spaces {
+        path = path.split_whitespace().collect::<Vec<_>>().join("-");
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_and_fix() {
+        let rules = LintConfig { max_command_length: Some(5), ..LintConfig::default() };
+        let cmd = Command::new("Docker/Prune All", "docker system prune", "");
+
+        let messages: Vec<String> = check(&cmd, &rules).into_iter().map(|p| p.message).collect();
+        assert_eq!(messages.len(), 4);
+        assert_eq!(fixed_path(&cmd.path, &rules), "docker/prune-all");
+    }
+
+    #[test]
+    fn test_clean_command() {
+        let cmd = Command::new("git/status", "git status", "Show status");
+        assert!(check(&cmd, &LintConfig::default()).is_empty());
+    }
+}
@@ -1,54 +1,176 @@
+use crate::command::Command;
 use crate::config::Config;
 use crate::error::{CmdxError, Result};
-use crate::store::Store;
+use crate::history::now;
+use crate::pager;
+use crate::store::{self};
+use crate::text::output_width;
 use colored::Colorize;
+use serde::Serialize;
 use std::collections::BTreeMap;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
-pub fn exec(path: Option<String>) -> Result<()> {
+use super::empty::print_empty_store;
+
+/// Shown after commands whose `expires:` date has passed.
+pub const EXPIRED_MARK: &str = "(expired)";
+
+/// Flags for `cmdx ls` beyond the path filter.
+#[derive(Debug, Default)]
+pub struct ListOptions {
+    pub categories: bool,
+    pub by_count: bool,
+    pub json: bool,
+    pub json_full: bool,
+    pub no_pager: bool,
+    pub long: bool,
+    pub files: bool,
+    pub plain: bool,
+}
+
+pub fn exec(path: Option<String>, opts: ListOptions) -> Result<()> {
+    let ListOptions { categories, by_count, json, json_full, no_pager, long, files, plain } = opts;
     let config = Config::load()?;
-    let store = Store::new(&config);
+    let store = store::open(&config)?;
 
     if !store.exists() {
         return Err(CmdxError::NotInitialized);
     }
 
-    let commands = store.list(path.as_deref())?;
+    let commands = store.list_matching(path.as_deref())?;
+
+    if json || json_full {
+        let tree = build_tree(&commands, json_full);
+        let out = serde_json::to_string_pretty(&tree)
+            .map_err(|e| CmdxError::Config(format!("Failed to serialize: {}", e)))?;
+        println!("{}", out);
+        return Ok(());
+    }
 
     if commands.is_empty() {
-        println!("{}", "No commands found.".dimmed());
+        match &path {
+            Some(p) => println!("{}", format!("No commands under {}.", p).dimmed()),
+            None => print_empty_store(),
+        }
+        return Ok(());
+    }
+
+    if categories {
+        print_categories(&commands, by_count);
         return Ok(());
     }
 
-    // Build tree structure
-    let tree = build_tree(&commands.iter().map(|c| c.path.as_str()).collect::<Vec<_>>());
-    
+    if files || plain {
+        // Only the filesystem backend has files to point at
+        let store = files.then(|| store::open_filesystem(&config)).transpose()?;
+        let rows: Vec<(&str, Option<String>)> = commands
+            .iter()
+            .map(|cmd| {
+                let file = store.as_ref().map(|s| s.command_path(&cmd.path).display().to_string());
+                (cmd.path.as_str(), file)
+            })
+            .collect();
+        print_flat(&rows, plain, no_pager);
+        return Ok(());
+    }
+
+    // Build tree structure; --long needs the command text at the leaves
+    let tree = build_tree(&commands, long);
+    let style = TreeStyle {
+        risk_colors: config.display.risk_colors,
+        long,
+        width: output_width(&config.display),
+        manual_order: config.display.order == "manual",
+    };
+
     let title = match &path {
         Some(p) => format!("cmdx/{}", p),
         None => "cmdx".to_string(),
     };
-    println!("{}", title.cyan().bold());
-    print_tree(&tree, "", true);
+    let mut out = format!("{}\n", title.cyan().bold());
+    print_tree(&tree, "", &style, &mut out);
+    pager::page(&out, !no_pager);
 
     Ok(())
 }
 
-#[derive(Debug, Default)]
+/// One command per line instead of the tree, with its file when known.
+/// `plain` drops color and alignment for scripts: `path<TAB>file`.
+fn print_flat(rows: &[(&str, Option<String>)], plain: bool, no_pager: bool) {
+    if plain {
+        for (path, file) in rows {
+            match file {
+                Some(file) => println!("{}\t{}", path, file),
+                None => println!("{}", path),
+            }
+        }
+        return;
+    }
+
+    let column = rows.iter().map(|(path, _)| path.width()).max().unwrap_or(0);
+    let mut out = String::new();
+    for (path, file) in rows {
+        let padding = " ".repeat(column - path.width());
+        out.push_str(&format!("{}{} {}\n", path.cyan(), padding, file.as_deref().unwrap_or("").dimmed()));
+    }
+    pager::page(&out, !no_pager);
+}
+
+fn print_categories(commands: &[Command], by_count: bool) {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for cmd in commands {
+        let category = cmd.path.split('/').next().unwrap_or("");
+        *counts.entry(category).or_default() += 1;
+    }
+
+    let mut categories: Vec<(&str, usize)> = counts.into_iter().collect();
+    if by_count {
+        // Stable sort keeps name order among equal counts
+        categories.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+    }
+
+    for (name, count) in categories {
+        println!("{} {}", name.yellow(), format!("({})", count).dimmed());
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
 struct TreeNode {
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
     children: BTreeMap<String, TreeNode>,
     is_leaf: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    command: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    explanation: Option<String>,
+    #[serde(skip)]
+    dangerous: bool,
+    #[serde(skip)]
+    order: Option<u32>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    expired: bool,
 }
 
-fn build_tree(paths: &[&str]) -> TreeNode {
+/// Build the path tree; with `full`, leaves carry the command and explanation.
+fn build_tree(commands: &[Command], full: bool) -> TreeNode {
     let mut root = TreeNode::default();
+    let now = now();
 
-    for path in paths {
-        let parts: Vec<&str> = path.split('/').collect();
+    for cmd in commands {
+        let parts: Vec<&str> = cmd.path.split('/').collect();
         let mut current = &mut root;
 
         for (i, part) in parts.iter().enumerate() {
             current = current.children.entry(part.to_string()).or_default();
             if i == parts.len() - 1 {
                 current.is_leaf = true;
+                current.dangerous = cmd.is_dangerous();
+                current.order = cmd.order;
+                current.expired = cmd.is_expired(now);
+                if full {
+                    current.command = Some(cmd.command.clone());
+                    current.explanation = Some(cmd.explanation.clone());
+                }
             }
         }
     }
@@ -56,8 +178,24 @@ fn build_tree(paths: &[&str]) -> TreeNode {
     root
 }
 
-fn print_tree(node: &TreeNode, prefix: &str, _is_last: bool) {
-    let children: Vec<_> = node.children.iter().collect();
+/// How `print_tree` renders leaves.
+struct TreeStyle {
+    /// Dangerous leaves are red instead of green.
+    risk_colors: bool,
+    /// Leaves show `name — command`, cut to `width`.
+    long: bool,
+    width: Option<usize>,
+    /// Siblings follow their `order:` meta, then name; else just name.
+    manual_order: bool,
+}
+
+/// Render the tree into `out`, one line per node.
+fn print_tree(node: &TreeNode, prefix: &str, style: &TreeStyle, out: &mut String) {
+    let mut children: Vec<_> = node.children.iter().collect();
+    if style.manual_order {
+        // Stable, so unordered entries (and categories) keep name order
+        children.sort_by_key(|(_, child)| (child.order.is_none(), child.order));
+    }
     let count = children.len();
 
     for (i, (name, child)) in children.into_iter().enumerate() {
@@ -65,14 +203,60 @@ fn print_tree(node: &TreeNode, prefix: &str, _is_last: bool) {
         let connector = if is_last_child { "└── " } else { "├── " };
         let next_prefix = if is_last_child { "    " } else { "│   " };
 
-        if child.is_leaf {
-            println!("{}{}{}", prefix, connector, name.green());
-        } else {
-            println!("{}{}{}", prefix, connector, name.yellow());
+        let mut used = prefix.width() + connector.width() + name.width();
+        if child.expired {
+            used += EXPIRED_MARK.width() + 1;
         }
+        let styled = match (child.is_leaf, style.risk_colors && child.dangerous) {
+            _ if child.expired => name.dimmed(),
+            (true, true) => name.red(),
+            (true, false) => name.green(),
+            (false, _) => name.yellow(),
+        };
+        out.push_str(&format!("{}{}{}", prefix, connector, styled));
+        if child.expired {
+            out.push_str(&format!(" {}", EXPIRED_MARK.dimmed()));
+        }
+        if let (true, Some(command)) = (style.long && child.is_leaf, &child.command) {
+            let room = style.width.map(|w| w.saturating_sub(used + 3));
+            out.push_str(&format!(" {} {}", "—".dimmed(), truncate(command, room).dimmed()));
+        }
+        out.push('\n');
 
         if !child.children.is_empty() {
-            print_tree(child, &format!("{}{}", prefix, next_prefix), is_last_child);
+            print_tree(child, &format!("{}{}", prefix, next_prefix), style, out);
         }
     }
 }
+
+/// Cut `text` to `max` columns, ending in `…` when shortened.
+fn truncate(text: &str, max: Option<usize>) -> String {
+    let Some(max) = max else { return text.to_string() };
+    if text.width() <= max {
+        return text.to_string();
+    }
+    let mut out = String::new();
+    let mut used = 0;
+    for c in text.chars() {
+        let w = c.width().unwrap_or(0);
+        if used + w + 1 > max {
+            break;
+        }
+        out.push(c);
+        used += w;
+    }
+    out.push('…');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("git status", None), "git status");
+        assert_eq!(truncate("git status", Some(10)), "git status");
+        assert_eq!(truncate("git status", Some(6)), "git s…");
+    }
+}
@@ -0,0 +1,39 @@
+use crate::config::Config;
+use crate::error::{CmdxError, Result};
+use crate::log::success;
+use crate::store::{self, CommandStore, STORE_VERSION};
+
+pub fn exec() -> Result<()> {
+    let config = Config::load()?;
+    let store = store::open_filesystem(&config)?;
+
+    if !store.exists() {
+        return Err(CmdxError::NotInitialized);
+    }
+
+    // Stores created before the manifest existed are version 1
+    let recorded = store.version()?;
+    let current = recorded.unwrap_or(1);
+
+    if current > STORE_VERSION {
+        return Err(CmdxError::Config(format!(
+            "Store format v{} is newer than this cmdx supports (v{})",
+            current, STORE_VERSION
+        )));
+    }
+
+    // Step-wise upgrades go here as the format evolves (v1 -> v2, ...).
+    // v2 only allows compact files, so v1 files are already valid v2.
+
+    store.write_version(STORE_VERSION)?;
+
+    if recorded.is_none() {
+        success!("Recorded store format v{}", STORE_VERSION);
+    } else if current == STORE_VERSION {
+        success!("Store is up to date (v{})", STORE_VERSION);
+    } else {
+        success!("Migrated store v{} → v{}", current, STORE_VERSION);
+    }
+
+    Ok(())
+}
@@ -11,18 +11,50 @@ mod mv;
 mod export;
 mod import;
 mod pick;
+mod confirm;
+mod empty;
+mod args;
+mod templates;
+mod history;
+mod migrate;
+mod lint;
+mod trash;
+mod replace;
+mod stats;
+mod prune;
+mod annotate;
+#[cfg(feature = "serve")]
+mod serve;
 
 pub use init::exec as init;
+pub use add::batch as add_batch;
 pub use add::exec as add;
+pub use add::AddOptions;
+pub use run::{Retry, RunOptions};
 pub use show::exec as show;
 pub use list::exec as list;
+pub use list::ListOptions;
 pub use find::exec as find;
+pub use find::{notes_score, FindOptions, NOTES_MIN_QUERY};
 pub use copy::exec as copy;
 pub use copy::copy_to_clipboard;
+pub use copy::last;
 pub use run::exec as run;
+pub use run::LAST_RUN;
 pub use edit::exec as edit;
 pub use remove::exec as remove;
 pub use mv::exec as mv;
 pub use export::exec as export;
 pub use import::exec as import;
 pub use pick::exec as pick;
+pub use templates::exec as templates;
+pub use history::exec as history;
+pub use migrate::exec as migrate;
+pub use lint::exec as lint;
+pub use replace::exec as replace;
+pub use stats::exec as stats;
+pub use prune::exec as prune;
+pub use annotate::exec as annotate;
+pub use trash::{clean as clean_trash, list as trash, undo};
+#[cfg(feature = "serve")]
+pub use serve::exec as serve;
@@ -1,23 +1,145 @@
+use crate::command::Command;
 use crate::config::Config;
 use crate::error::{CmdxError, Result};
-use crate::store::Store;
+use crate::log::success;
+use crate::store::{self, validate_path, CommandStore};
 use colored::Colorize;
+use std::collections::BTreeSet;
+use std::io::{self, Write};
+use std::path::PathBuf;
 
-pub fn exec(src: String, dst: String) -> Result<()> {
+pub fn exec(src: String, dst: Option<String>, interactive: bool, alias: bool, dry_run: bool) -> Result<()> {
     let config = Config::load()?;
-    let store = Store::new(&config);
+    let store = store::open(&config)?;
 
     if !store.exists() {
         return Err(CmdxError::NotInitialized);
     }
 
+    let dst = match dst {
+        Some(d) if !interactive => d,
+        _ => match prompt_destination(store.as_ref(), &src)? {
+            Some(d) => d,
+            None => {
+                println!("{}", "Cancelled.".dimmed());
+                return Ok(());
+            }
+        },
+    };
+
     // Validate destination path
-    if dst.is_empty() || dst.starts_with('/') || dst.contains("..") {
-        return Err(CmdxError::InvalidPath(dst));
+    validate_path(&dst)?;
+
+    if dry_run {
+        return preview(store.as_ref(), &src, &dst, alias, config.core.prune_empty_dirs);
     }
 
     store.rename(&src, &dst)?;
-    println!("{} Moved {} → {}", "✓".green(), src.cyan(), dst.cyan());
+    success!("Moved {} → {}", src.cyan(), dst.cyan());
+
+    if alias {
+        store.add(&Command::alias_to(&src, &dst), false)?;
+        success!("Left alias {} → {}", src.cyan(), dst.cyan());
+    }
+
+    Ok(())
+}
+
+/// `--dry-run`: report what the move would do, failing on the same
+/// conflicts the real move would hit, without touching the store.
+fn preview(store: &dyn CommandStore, src: &str, dst: &str, alias: bool, prune: bool) -> Result<()> {
+    store.get_entry(src)?;
 
+    // Anything other than "not found" (a command, alias or category) blocks it
+    if !matches!(store.get_entry(dst), Err(CmdxError::NotFound(_))) {
+        return Err(CmdxError::AlreadyExists(PathBuf::from(dst)));
+    }
+
+    println!("{} {} → {}", "Would move".dimmed(), src.cyan(), dst.cyan());
+    if alias {
+        println!("{} {} → {}", "Would leave alias".dimmed(), src.cyan(), dst.cyan());
+    } else if prune {
+        let paths: Vec<String> = store.list(None)?.into_iter().map(|c| c.path).collect();
+        for dir in emptied_categories(&paths, src, dst) {
+            println!("{} {}/", "Would remove empty category".dimmed(), dir.yellow());
+        }
+    }
+    println!("{}", "Dry run: nothing was changed.".dimmed());
     Ok(())
 }
+
+/// Categories of `src`, deepest first, that hold nothing else once it moves
+/// to `dst` (the ones `prune_empty_dirs` would delete).
+fn emptied_categories(paths: &[String], src: &str, dst: &str) -> Vec<String> {
+    let mut emptied = Vec::new();
+    let mut current = src;
+    while let Some((dir, _)) = current.rsplit_once('/') {
+        let prefix = format!("{}/", dir);
+        let occupied = paths.iter().any(|p| p != src && p.starts_with(&prefix)) || dst.starts_with(&prefix);
+        if occupied {
+            break;
+        }
+        emptied.push(dir.to_string());
+        current = dir;
+    }
+    emptied
+}
+
+/// Offer existing categories as numbered choices; a typed path is also accepted.
+fn prompt_destination(store: &dyn CommandStore, src: &str) -> Result<Option<String>> {
+    // Verify the source exists before asking anything
+    store.get_entry(src)?;
+
+    let categories = categories(&store.list(None)?.into_iter().map(|c| c.path).collect::<Vec<_>>());
+    let name = src.rsplit('/').next().unwrap_or(src);
+
+    for (i, category) in categories.iter().enumerate() {
+        println!("  {} {}", format!("{:>2})", i + 1).dimmed(), category.yellow());
+    }
+    print!("Destination category [number or path]: ");
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    let input = input.trim().trim_end_matches('/');
+
+    if input.is_empty() {
+        return Ok(None);
+    }
+
+    let category = match input.parse::<usize>() {
+        Ok(n) => match n.checked_sub(1).and_then(|i| categories.get(i)) {
+            Some(c) => c.clone(),
+            None => return Err(CmdxError::InvalidPath(input.to_string())),
+        },
+        Err(_) => input.to_string(),
+    };
+
+    Ok(Some(format!("{}/{}", category, name)))
+}
+
+/// Every category (directory) prefix of the given command paths.
+fn categories(paths: &[String]) -> Vec<String> {
+    let mut set = BTreeSet::new();
+    for path in paths {
+        let parts: Vec<&str> = path.split('/').collect();
+        for i in 1..parts.len() {
+            set.insert(parts[..i].join("/"));
+        }
+    }
+    set.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_emptied_categories() {
+        let paths: Vec<String> = ["old/sub/cmd", "old/other", "tools/x"].iter().map(|p| p.to_string()).collect();
+        assert_eq!(emptied_categories(&paths, "old/sub/cmd", "new/cmd"), vec!["old/sub"]);
+        assert_eq!(emptied_categories(&paths, "tools/x", "bin/x"), vec!["tools"]);
+        assert!(emptied_categories(&paths, "tools/x", "tools/y").is_empty());
+        assert!(emptied_categories(&paths, "old/other", "new/other").is_empty());
+    }
+}
@@ -1,14 +1,23 @@
+use crate::command::Command;
 use crate::config::Config;
 use crate::error::{CmdxError, Result};
-use crate::store::Store;
-use crate::tui;
+use crate::log::{esuccess, success};
+use crate::store;
+use crate::tui::{self, ChangeLog, PickAction};
 use colored::Colorize;
+use std::fs;
+use std::path::Path;
 
+use super::args::prompt_args;
+use super::copy::copy_command;
 use super::copy_to_clipboard;
+use super::empty::print_empty_store;
+use super::export::ExportData;
+use super::run::{run_command, Retry};
 
-pub fn exec() -> Result<()> {
+pub fn exec(multi: bool, export: Option<String>) -> Result<()> {
     let config = Config::load()?;
-    let store = Store::new(&config);
+    let store = store::open(&config)?;
 
     if !store.exists() {
         return Err(CmdxError::NotInitialized);
@@ -17,25 +26,27 @@ pub fn exec() -> Result<()> {
     let commands = store.list(None)?;
 
     if commands.is_empty() {
-        println!("No commands found. Add some with 'cmdx add'.");
+        print_empty_store();
         return Ok(());
     }
 
     // Run the TUI picker
-    match tui::run(commands)? {
-        Some(cmd) => {
-            // Copy to clipboard
-            if copy_to_clipboard(&cmd.command, &config.clipboard.tool) {
-                println!("{} Copied: {}", "✓".green(), cmd.path.cyan());
-            } else {
-                // Fallback: print the command
-                println!("{}", cmd.path.cyan());
-                println!("{}", cmd.command);
-                if !cmd.explanation.is_empty() {
-                    println!("{} {}", "→".dimmed(), cmd.explanation.dimmed());
-                }
-            }
+    let session = tui::run_session(commands, "", multi)?;
+    print_changes(&session.changes);
+
+    if multi {
+        return match export {
+            Some(file) if !session.marked.is_empty() => export_marked(session.marked, Path::new(&file)),
+            None if !session.marked.is_empty() => copy_marked(&session.marked, &config),
+            _ => Ok(()),
+        };
+    }
+
+    match session.picked {
+        Some((cmd, PickAction::Run)) => {
+            run_command(&cmd, &config, false, false, &[], false, Retry::default())?;
         }
+        Some((cmd, PickAction::Copy)) => copy_command(&cmd, &config)?,
         None => {
             // User cancelled
         }
@@ -43,3 +54,53 @@ pub fn exec() -> Result<()> {
 
     Ok(())
 }
+
+/// `--multi --export`: write the marked commands as an `export` file.
+fn export_marked(commands: Vec<Command>, file: &Path) -> Result<()> {
+    let export_data = ExportData { version: 1, commands, config: None };
+    let json = serde_json::to_string_pretty(&export_data)
+        .map_err(|e| CmdxError::Config(format!("Failed to serialize: {}", e)))?;
+    fs::write(file, json)?;
+    success!("Exported {} commands to {}", export_data.commands.len(), file.display());
+    Ok(())
+}
+
+/// `--multi` alone: copy the marked commands, one per line.
+fn copy_marked(commands: &[Command], config: &Config) -> Result<()> {
+    let mut lines = Vec::new();
+    for cmd in commands {
+        lines.push(config.display_command(&prompt_args(cmd)?.command));
+    }
+    let text = lines.join("\n");
+
+    if copy_to_clipboard(&text, &config.clipboard.tool) {
+        esuccess!("Copied {} commands", commands.len());
+    } else {
+        println!("{}", text);
+    }
+    Ok(())
+}
+
+/// Summarize what the session added, edited and deleted; silent if nothing.
+fn print_changes(changes: &ChangeLog) {
+    if changes.is_empty() {
+        return;
+    }
+
+    println!(
+        "{} {} added, {} edited, {} deleted",
+        "Session:".dimmed(),
+        changes.added.len(),
+        changes.edited.len(),
+        changes.deleted.len()
+    );
+    for path in &changes.added {
+        println!("  {} {}", "+".green(), path);
+    }
+    for path in &changes.edited {
+        println!("  {} {}", "~".yellow(), path);
+    }
+    for path in &changes.deleted {
+        println!("  {} {}", "-".red(), path);
+    }
+}
@@ -0,0 +1,81 @@
+use crate::command::Command;
+use crate::config::Config;
+use crate::error::{CmdxError, Result};
+use crate::history::now;
+use crate::log::success;
+use crate::store::{self, CommandStore};
+use crate::trash::Trash;
+use colored::Colorize;
+
+use super::confirm::confirm_affected;
+
+/// `cmdx prune --expired`: move commands past their `expires:` date to the trash.
+pub fn exec(dry_run: bool, force: bool) -> Result<()> {
+    let config = Config::load()?;
+    let store = store::open(&config)?;
+
+    if !store.exists() {
+        return Err(CmdxError::NotInitialized);
+    }
+
+    let expired = expired_commands(store.as_ref(), now())?;
+    if expired.is_empty() {
+        println!("{}", "No expired commands.".dimmed());
+        return Ok(());
+    }
+
+    if dry_run {
+        for cmd in &expired {
+            println!("{} {}", cmd.path.cyan(), format!("(expired {})", cmd.expires).dimmed());
+        }
+        return Ok(());
+    }
+
+    if !force && !confirm_affected(&expired, "Remove", config.core.confirm_default)? {
+        println!("{}", "Cancelled.".dimmed());
+        return Ok(());
+    }
+
+    let trash = Trash::new();
+    for cmd in &expired {
+        trash.put(cmd)?;
+        store.remove(&cmd.path)?;
+    }
+
+    success!(
+        "Removed {} expired command{} {}",
+        expired.len(),
+        if expired.len() == 1 { "" } else { "s" },
+        "(restore with 'cmdx undo')".dimmed()
+    );
+    Ok(())
+}
+
+fn expired_commands(store: &dyn CommandStore, now: u64) -> Result<Vec<Command>> {
+    let mut commands = store.list(None)?;
+    commands.retain(|cmd| cmd.is_expired(now));
+    Ok(commands)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expired_commands() {
+        let store = store::MemoryStore::default();
+        let mut old = Command::new("tmp/old", "true", "");
+        old.expires = "2026-10-01 00:00:00".to_string();
+        let mut later = Command::new("tmp/later", "true", "");
+        later.expires = "2026-12-01".to_string();
+        for cmd in [old, later, Command::new("tmp/kept", "true", "")] {
+            store.add(&cmd, false).unwrap();
+        }
+
+        let paths = |now| -> Vec<String> {
+            expired_commands(&store, now).unwrap().into_iter().map(|c| c.path).collect()
+        };
+        assert_eq!(paths(1_792_195_200), vec!["tmp/old"]);
+        assert_eq!(paths(u64::MAX), vec!["tmp/later", "tmp/old"]);
+    }
+}
@@ -1,37 +1,66 @@
 use crate::config::Config;
 use crate::error::{CmdxError, Result};
-use crate::store::Store;
+use crate::log::success;
+use crate::store::{self, CommandStore};
+use crate::trash::Trash;
 use colored::Colorize;
 use std::io::{self, Write};
 
-pub fn exec(path: String, force: bool) -> Result<()> {
+use super::confirm::{choices, confirm_affected, read_yes};
+
+pub fn exec(path: String, force: bool, recursive: bool) -> Result<()> {
     let config = Config::load()?;
-    let store = Store::new(&config);
+    let store = store::open(&config)?;
 
     if !store.exists() {
         return Err(CmdxError::NotInitialized);
     }
 
+    if recursive {
+        return remove_recursive(store.as_ref(), &path, force, config.core.confirm_default);
+    }
+
     // Verify exists
-    let cmd = store.get(&path)?;
+    let cmd = store.get_entry(&path)?;
 
     if !force {
         println!("{}", cmd.path.cyan());
         println!("  {}", cmd.command.white());
-        print!("Remove? [y/N] ");
+        print!("Remove? {} ", choices(config.core.confirm_default));
         io::stdout().flush()?;
 
-        let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
-
-        if !input.trim().eq_ignore_ascii_case("y") {
+        if !read_yes(config.core.confirm_default)? {
             println!("{}", "Cancelled.".dimmed());
             return Ok(());
         }
     }
 
+    Trash::new().put(&cmd)?;
     store.remove(&path)?;
-    println!("{} Removed {}", "✓".green(), path.cyan());
+    success!("Removed {} {}", path.cyan(), "(restore with 'cmdx undo')".dimmed());
+
+    Ok(())
+}
+
+fn remove_recursive(store: &dyn CommandStore, path: &str, force: bool, default_yes: bool) -> Result<()> {
+    let commands = store.list(Some(path))?;
+
+    if commands.is_empty() {
+        return Err(CmdxError::NotFound(path.to_string()));
+    }
+
+    if !force && !confirm_affected(&commands, "Remove", default_yes)? {
+        println!("{}", "Cancelled.".dimmed());
+        return Ok(());
+    }
+
+    let trash = Trash::new();
+    for cmd in &commands {
+        trash.put(cmd)?;
+        store.remove(&cmd.path)?;
+    }
+
+    success!("Removed {} commands under {}", commands.len(), path.cyan());
 
     Ok(())
 }
@@ -0,0 +1,85 @@
+use crate::command::Command;
+use crate::config::Config;
+use crate::error::{CmdxError, Result};
+use crate::log::success;
+use crate::store;
+use colored::Colorize;
+use regex::Regex;
+use std::io::{self, Write};
+
+use super::confirm::{choices, read_yes};
+
+pub fn exec(
+    from: String,
+    to: String,
+    prefix: Option<String>,
+    regex: bool,
+    dry_run: bool,
+    force: bool,
+) -> Result<()> {
+    let config = Config::load()?;
+    let store = store::open(&config)?;
+
+    if !store.exists() {
+        return Err(CmdxError::NotInitialized);
+    }
+
+    let pattern = if regex {
+        Some(Regex::new(&from).map_err(|e| CmdxError::Config(format!("Invalid regex: {}", e)))?)
+    } else {
+        None
+    };
+
+    // (old command text, rewritten command)
+    let changed: Vec<(String, Command)> = store
+        .list(prefix.as_deref())?
+        .into_iter()
+        .filter_map(|cmd| {
+            let replaced = match &pattern {
+                Some(re) => re.replace_all(&cmd.command, to.as_str()).into_owned(),
+                None => cmd.command.replace(&from, &to),
+            };
+            (replaced != cmd.command).then(|| {
+                let old = cmd.command.clone();
+                (old, Command { command: replaced, ..cmd })
+            })
+        })
+        .collect();
+
+    if changed.is_empty() {
+        println!("{} No commands contain {}", "!".yellow(), from.cyan());
+        return Ok(());
+    }
+
+    for (old, cmd) in &changed {
+        println!("{}", cmd.path.cyan());
+        println!("  {} {}", "-".red(), old.dimmed());
+        println!("  {} {}", "+".green(), cmd.command.white());
+    }
+
+    if dry_run {
+        println!("{} {} command(s) would change", "~".yellow(), changed.len());
+        return Ok(());
+    }
+
+    if !force {
+        print!(
+            "Rewrite {} command(s)? {} ",
+            changed.len(),
+            choices(config.core.confirm_default)
+        );
+        io::stdout().flush()?;
+
+        if !read_yes(config.core.confirm_default)? {
+            println!("{}", "Cancelled.".dimmed());
+            return Ok(());
+        }
+    }
+
+    for (_, cmd) in &changed {
+        store.add(cmd, true)?;
+    }
+
+    success!("Updated {} command(s)", changed.len());
+    Ok(())
+}
@@ -1,56 +1,497 @@
+use crate::command::Command;
 use crate::config::Config;
 use crate::error::{CmdxError, Result};
-use crate::store::Store;
-use crate::commands::find::best_match;
+use crate::history::{self, HistoryEntry};
+use crate::log::{esuccess, verbose};
+use crate::store;
+use crate::text::{format_explanation, output_width};
+use crate::commands::args::{prompt_args, prompt_args_remembered};
+use crate::commands::confirm::{choices, read_answer, read_yes};
+use crate::commands::copy_to_clipboard;
+use crate::commands::empty::print_empty_store;
+use crate::commands::find::resolve_interactive;
 use colored::Colorize;
-use std::io::{self, Write};
-use std::process::Command as Process;
+use std::io::{self, Read, Write};
+use signal_hook::consts::SIGINT;
+use signal_hook::SigId;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command as Process, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
 
-pub fn exec(query: String, confirm: bool) -> Result<()> {
+/// Query that stands for the most recently run command, like a shell's `!!`.
+pub const LAST_RUN: &str = "-";
+
+/// Flags for `cmdx run` beyond the query.
+#[derive(Debug, Default)]
+pub struct RunOptions {
+    pub confirm: bool,
+    pub raw: bool,
+    pub no_prompt: bool,
+    pub env_file: Option<String>,
+    pub copy_output: bool,
+    pub cwd: Option<String>,
+    pub interactive_args: bool,
+    pub reset_args: bool,
+    pub retry: Retry,
+}
+
+/// How often to re-run a command that exits non-zero, and how long to wait
+/// between attempts. The default runs once.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Retry {
+    pub times: u32,
+    pub delay: Duration,
+}
+
+pub fn exec(query: String, opts: RunOptions) -> Result<()> {
+    let RunOptions { confirm, raw, no_prompt, env_file, copy_output, cwd, interactive_args, reset_args, retry } = opts;
     let config = Config::load()?;
-    let store = Store::new(&config);
+    let store = store::open(&config)?;
 
     if !store.exists() {
         return Err(CmdxError::NotInitialized);
     }
 
-    // Try exact match first, then fuzzy
-    let cmd = match store.get(&query) {
-        Ok(c) => c,
-        Err(_) => {
-            let commands = store.list(None)?;
-            best_match(&query, &commands)
-                .cloned()
-                .ok_or_else(|| CmdxError::NotFound(query.clone()))?
+    let env = match env_file {
+        Some(path) => load_env_file(Path::new(&path))?,
+        None => vec![],
+    };
+
+    let mut cmd = if query == LAST_RUN {
+        let last = history::load()?.pop().ok_or(CmdxError::NoHistory)?;
+        verbose!("rerunning last history entry: {}", last.path);
+        // Run the stored version so edits since the last run are picked up
+        store.get(&last.path)?
+    } else {
+        // Try exact match first, then fuzzy
+        match store.get(&query) {
+            Ok(c) => {
+                verbose!("exact path match: {}", c.path);
+                c
+            }
+            Err(_) => {
+                verbose!("no exact path '{}', falling back to fuzzy match", query);
+                let commands = store.list(None)?;
+                if commands.is_empty() {
+                    print_empty_store();
+                    return Err(CmdxError::NotFound(query));
+                }
+                match resolve_interactive(&query, &commands, !no_prompt)? {
+                    Some(c) => {
+                        // Say which command the query landed on before it runs
+                        if !raw || confirm {
+                            eprintln!("{} {}", "Matched:".dimmed(), c.path.cyan());
+                        }
+                        c.clone()
+                    }
+                    None => {
+                        eprintln!("{}", "Cancelled.".dimmed());
+                        return Ok(());
+                    }
+                }
+            }
         }
     };
 
-    println!("{} {}", "Running:".dimmed(), cmd.command.white().bold());
+    if let Some(dir) = cwd {
+        cmd.cwd = dir;
+    }
+
+    if reset_args && history::forget_args(&cmd.path)? {
+        esuccess!("Forgot remembered arguments for {}", cmd.path);
+    }
+
+    // Filling every placeholder here leaves nothing for run_command to ask
+    if interactive_args {
+        cmd = match prompt_args_remembered(&cmd)? {
+            Some(filled) => filled,
+            None => {
+                eprintln!("{}", "Cancelled.".dimmed());
+                return Ok(());
+            }
+        };
+    }
+
+    run_command(&cmd, &config, confirm, raw, &env, copy_output, retry)
+}
+
+/// Execute an already-resolved command through the configured shell.
+///
+/// `env` is applied after `[run] env` from the config, so it takes precedence.
+/// A failing command is re-run as `retry` allows; only the last attempt's
+/// output is copied with `copy_output`. Commands under `[safety]
+/// type_to_confirm` are confirmed by retyping their path, even without `confirm`.
+pub fn run_command(
+    cmd: &Command,
+    config: &Config,
+    confirm: bool,
+    raw: bool,
+    env: &[(String, String)],
+    copy_output: bool,
+    retry: Retry,
+) -> Result<()> {
+    let cmd = &prompt_args(cmd)?;
+
+    // Checked up front so a typo'd cwd doesn't run the command elsewhere
+    let cwd = working_dir(cmd)?;
+    let shell_args = shell_args(cmd, config)?;
+
+    // Commands covered by [safety] type_to_confirm are always confirmed
+    let typed_confirm = config.requires_typed_confirm(cmd);
+    let confirm = confirm || typed_confirm;
+
+    // Diagnostics go to stderr so the command's stdout stays clean for piping.
+    // The shell does its own expansion, so only the preview uses display_command.
+    if !raw || confirm {
+        if let Some(dir) = &cwd {
+            eprintln!("{} {}", "In:".dimmed(), dir.display());
+        }
+        let preview = config.display_command(&cmd.command);
+        eprintln!("{} {}", "Running:".dimmed(), preview.white().bold());
+    }
 
     if confirm {
-        print!("Execute? [y/N] ");
-        io::stdout().flush()?;
+        if !cmd.explanation.is_empty() {
+            eprint!("{}", format_explanation(&cmd.explanation, 9, output_width(&config.display)));
+        }
 
-        let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
+        let prompt = config.confirm_prompt(cmd);
+        if typed_confirm {
+            eprint!("{} {} ", prompt, format!("Type {} to confirm:", cmd.path).dimmed());
+        } else {
+            eprint!("{} {} ", prompt, choices(config.core.confirm_default));
+        }
+        io::stderr().flush()?;
 
-        if !input.trim().eq_ignore_ascii_case("y") {
-            println!("{}", "Cancelled.".dimmed());
+        let confirmed = if typed_confirm {
+            read_answer()?.is_some_and(|input| input.trim() == cmd.path)
+        } else {
+            read_yes(config.core.confirm_default)?
+        };
+        if !confirmed {
+            eprintln!("{}", "Cancelled.".dimmed());
             return Ok(());
         }
     }
 
-    let shell = &config.core.shell;
-    let status = Process::new(shell)
-        .arg("-c")
-        .arg(&cmd.command)
-        .status()
-        .map_err(|e| CmdxError::Execution(e.to_string()))?;
+    // Held across attempts so Ctrl+C between them (e.g. in the retry delay)
+    // stops the loop too
+    let sigint = SigintGuard::register();
+    let attempts = retry.times.saturating_add(1);
+    let mut attempt = 1;
+    let (status, code, captured) = loop {
+        let (status, code, captured) =
+            spawn(cmd, config, &shell_args, cwd.as_deref(), env, copy_output, &sigint.interrupted)?;
+        if status.success() {
+            if attempt > 1 {
+                esuccess!("Attempt {}/{} succeeded", attempt, attempts);
+            }
+            break (status, code, captured);
+        }
+        if attempt >= attempts {
+            if attempts > 1 {
+                eprintln!("{} Attempt {}/{} failed (exit code {}); giving up", "✗".red(), attempt, attempts, code);
+            }
+            break (status, code, captured);
+        }
+
+        eprintln!("{} Attempt {}/{} failed (exit code {}); retrying", "!".yellow(), attempt, attempts, code);
+        retry_pause(retry.delay, &sigint.interrupted)?;
+        attempt += 1;
+    };
+    drop(sigint);
 
     if !status.success() {
-        let code = status.code().unwrap_or(-1);
+        if copy_output {
+            eprintln!("{} Command failed; output not copied", "!".yellow());
+        }
         return Err(CmdxError::Execution(format!("Exit code: {}", code)));
     }
 
+    if let Some(captured) = captured {
+        let captured = captured?;
+        let text = captured.trim_end_matches(['\n', '\r']);
+        if copy_to_clipboard(text, &config.clipboard.tool) {
+            esuccess!("Copied output of {}", cmd.path.cyan());
+        } else {
+            eprintln!("{} Clipboard unavailable", "!".yellow());
+        }
+    }
+
     Ok(())
 }
+
+/// Catches SIGINT while cmdx waits on a command: Ctrl+C reaches the child
+/// through the process group and sets `interrupted` instead of killing cmdx.
+///
+/// Dropping it restores the default action. Plain `unregister` would leave
+/// the signal ignored, so Ctrl+C would stop working (e.g. in `cmdx repl`).
+struct SigintGuard {
+    interrupted: Arc<AtomicBool>,
+    id: Option<SigId>,
+}
+
+/// The default-emulating action left behind by the last [`SigintGuard`].
+static DEFAULT_SIGINT: Mutex<Option<SigId>> = Mutex::new(None);
+
+impl SigintGuard {
+    fn register() -> Self {
+        let interrupted = Arc::new(AtomicBool::new(false));
+        let id = signal_hook::flag::register(SIGINT, Arc::clone(&interrupted)).ok();
+        if id.is_some() {
+            if let Some(default) = DEFAULT_SIGINT.lock().unwrap_or_else(|e| e.into_inner()).take() {
+                signal_hook::low_level::unregister(default);
+            }
+        }
+        Self { interrupted, id }
+    }
+}
+
+impl Drop for SigintGuard {
+    fn drop(&mut self) {
+        let Some(id) = self.id.take() else { return };
+
+        // SAFETY: emulate_default_handler is async-signal-safe
+        let default = unsafe {
+            signal_hook::low_level::register(SIGINT, || {
+                let _ = signal_hook::low_level::emulate_default_handler(SIGINT);
+            })
+        };
+        signal_hook::low_level::unregister(id);
+        if let Ok(default) = default {
+            *DEFAULT_SIGINT.lock().unwrap_or_else(|e| e.into_inner()) = Some(default);
+        }
+    }
+}
+
+/// Sleep `delay` between attempts, giving up early once Ctrl+C was pressed.
+fn retry_pause(delay: Duration, interrupted: &AtomicBool) -> Result<()> {
+    const STEP: Duration = Duration::from_millis(50);
+
+    let mut left = delay;
+    loop {
+        if interrupted.load(Ordering::Relaxed) {
+            return Err(CmdxError::Interrupted);
+        }
+        if left.is_zero() {
+            return Ok(());
+        }
+        let step = left.min(STEP);
+        thread::sleep(step);
+        left -= step;
+    }
+}
+
+/// Run `cmd` once and record it in the history. Returns the exit status,
+/// the exit code (128 + signal when killed) and the captured stdout when
+/// `capture` is set.
+fn spawn(
+    cmd: &Command,
+    config: &Config,
+    shell_args: &[String],
+    cwd: Option<&Path>,
+    env: &[(String, String)],
+    capture: bool,
+    interrupted: &AtomicBool,
+) -> Result<(ExitStatus, i32, Option<Result<String>>)> {
+    let shell = &config.core.shell;
+    verbose!("invoking {} {} <command>", shell, shell_args.join(" "));
+    let mut process = Process::new(shell);
+    process
+        .args(shell_args)
+        .arg(&cmd.command)
+        .envs(&config.run.env)
+        .envs(env.iter().map(|(k, v)| (k, v)));
+    if let Some(dir) = cwd {
+        process.current_dir(dir);
+    }
+    if capture {
+        process.stdout(Stdio::piped());
+    }
+    let mut child = process
+        .spawn()
+        .map_err(|e| CmdxError::Execution(e.to_string()))?;
+
+    // Handlers are reset in the child on exec, so Ctrl+C still stops it
+    let captured = child.stdout.take().map(tee_stdout);
+    let status = child.wait().map_err(|e| CmdxError::Execution(e.to_string()))?;
+
+    let signal = exit_signal(&status);
+    let code = status.code().or(signal.map(|s| 128 + s)).unwrap_or(-1);
+    if let Err(e) = history::append(&HistoryEntry::new(&cmd.path, &cmd.command, code)) {
+        verbose!("failed to record history: {}", e);
+    }
+
+    // An interrupted attempt is never retried
+    if signal == Some(SIGINT) || interrupted.load(Ordering::Relaxed) {
+        return Err(CmdxError::Interrupted);
+    }
+
+    Ok((status, code, captured))
+}
+
+/// Echo the child's stdout as it arrives while keeping a copy.
+fn tee_stdout(mut stdout: impl Read) -> Result<String> {
+    let mut captured = Vec::new();
+    let mut buf = [0u8; 8192];
+    let mut out = io::stdout();
+
+    loop {
+        let n = match stdout.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
+        };
+        out.write_all(&buf[..n])?;
+        out.flush()?;
+        captured.extend_from_slice(&buf[..n]);
+    }
+
+    Ok(String::from_utf8_lossy(&captured).into_owned())
+}
+
+/// Shell arguments for `cmd`: its `shell_args:` meta line, else `[run] shell_args`.
+///
+/// The command text is always appended after them, so the last one must be
+/// the option that reads it (`-c`, `-lc`, `/C`).
+fn shell_args(cmd: &Command, config: &Config) -> Result<Vec<String>> {
+    if cmd.shell_args.is_empty() {
+        return config_shell_args(config);
+    }
+    check_shell_args(cmd.shell_args.split_whitespace().map(str::to_string).collect())
+}
+
+/// `[run] shell_args`, for running lines that aren't stored commands
+/// (`find --exec`). Checked like [`shell_args`].
+pub fn config_shell_args(config: &Config) -> Result<Vec<String>> {
+    check_shell_args(config.run.shell_args.clone())
+}
+
+fn check_shell_args(args: Vec<String>) -> Result<Vec<String>> {
+    match args.last() {
+        Some(last) if last.starts_with('-') || last.starts_with('/') => Ok(args),
+        _ => Err(CmdxError::Config(format!(
+            "shell_args {:?} must end with the option that takes the command, such as -c",
+            args
+        ))),
+    }
+}
+
+/// The command's `cwd` with `~` expanded, if it has one and it exists.
+fn working_dir(cmd: &Command) -> Result<Option<PathBuf>> {
+    if cmd.cwd.is_empty() {
+        return Ok(None);
+    }
+
+    let dir = PathBuf::from(shellexpand::tilde(&cmd.cwd).as_ref());
+    if !dir.is_dir() {
+        return Err(CmdxError::MissingCwd(dir));
+    }
+    Ok(Some(dir))
+}
+
+/// Parse a dotenv-style file: `KEY=VALUE` lines, `#` comments and blank
+/// lines ignored, optional `export ` prefix and surrounding quotes.
+fn load_env_file(path: &Path) -> Result<Vec<(String, String)>> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| CmdxError::Config(format!("Cannot read env file {}: {}", path.display(), e)))?;
+    parse_env(&content)
+        .map_err(|line| CmdxError::Config(format!("{}:{}: expected KEY=VALUE", path.display(), line)))
+}
+
+/// Returns the 1-based number of the first malformed line on error.
+fn parse_env(content: &str) -> std::result::Result<Vec<(String, String)>, usize> {
+    let mut vars = Vec::new();
+
+    for (i, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let (key, value) = line.split_once('=').ok_or(i + 1)?;
+        let key = key.trim();
+
+        if key.is_empty() || key.contains(char::is_whitespace) {
+            return Err(i + 1);
+        }
+
+        let value = value.trim();
+        let value = ['"', '\'']
+            .iter()
+            .find_map(|q| value.strip_prefix(*q).and_then(|v| v.strip_suffix(*q)))
+            .unwrap_or(value);
+
+        vars.push((key.to_string(), value.to_string()));
+    }
+
+    Ok(vars)
+}
+
+#[cfg(unix)]
+fn exit_signal(status: &ExitStatus) -> Option<i32> {
+    use std::os::unix::process::ExitStatusExt;
+    status.signal()
+}
+
+#[cfg(not(unix))]
+fn exit_signal(_status: &ExitStatus) -> Option<i32> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_env() {
+        let content = "# deploy\nexport APP_ENV=prod\n\nTOKEN=\"abc def\"\nEMPTY=\n";
+        let vars = parse_env(content).unwrap();
+        assert_eq!(
+            vars,
+            vec![
+                ("APP_ENV".to_string(), "prod".to_string()),
+                ("TOKEN".to_string(), "abc def".to_string()),
+                ("EMPTY".to_string(), String::new()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_shell_args_override_and_validation() {
+        let config = Config::default();
+        let mut cmd = Command::new("t/x", "echo hi", "");
+        assert_eq!(shell_args(&cmd, &config).unwrap(), vec!["-c"]);
+
+        cmd.shell_args = "-e -lc".to_string();
+        assert_eq!(shell_args(&cmd, &config).unwrap(), vec!["-e", "-lc"]);
+
+        cmd.shell_args = "-c extra".to_string();
+        assert!(shell_args(&cmd, &config).is_err());
+    }
+
+    #[test]
+    fn test_retry_pause_stops_on_interrupt() {
+        let interrupted = AtomicBool::new(false);
+        assert!(retry_pause(Duration::ZERO, &interrupted).is_ok());
+        assert!(retry_pause(Duration::from_millis(10), &interrupted).is_ok());
+
+        // Ctrl+C during an earlier attempt or the delay skips the rest of it
+        interrupted.store(true, Ordering::Relaxed);
+        let start = std::time::Instant::now();
+        assert!(matches!(retry_pause(Duration::from_secs(30), &interrupted), Err(CmdxError::Interrupted)));
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_parse_env_malformed() {
+        assert_eq!(parse_env("A=1\nnot a pair\n"), Err(2));
+        assert_eq!(parse_env("=value"), Err(1));
+    }
+}
@@ -0,0 +1,181 @@
+use crate::config::Config;
+use crate::error::{CmdxError, Result};
+use crate::log::esuccess;
+use crate::store::{self, CommandStore};
+use colored::Colorize;
+use serde::Serialize;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::time::Duration;
+
+use super::export::ExportData;
+use super::find::fuzzy_search;
+
+/// How long a client may take to send its request line or read the response.
+const IO_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub fn exec(host: String, port: u16) -> Result<()> {
+    let config = Config::load()?;
+    let store = store::open(&config)?;
+
+    if !store.exists() {
+        return Err(CmdxError::NotInitialized);
+    }
+
+    let listener = TcpListener::bind((host.as_str(), port))?;
+    esuccess!("Serving {} on http://{}:{}", config.store_path()?.display(), host, port);
+
+    // Requests are tiny and local, so one at a time is plenty
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                if let Err(e) = handle(stream, store.as_ref()) {
+                    eprintln!("{} {}", "!".yellow(), e);
+                }
+            }
+            Err(e) => eprintln!("{} {}", "!".yellow(), e),
+        }
+    }
+
+    Ok(())
+}
+
+fn handle(mut stream: TcpStream, store: &dyn CommandStore) -> Result<()> {
+    // Connections are served one at a time, so a silent client must not stall the rest
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+
+    let mut request_line = String::new();
+    BufReader::new(&stream).read_line(&mut request_line)?;
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or("");
+    let target = parts.next().unwrap_or("/");
+
+    let (status, body) = if method != "GET" {
+        (405, error_body("Only GET is supported"))
+    } else {
+        route(target, store)
+    };
+
+    let reason = match status {
+        200 => "OK",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Internal Server Error",
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason,
+        body.len(),
+        body
+    )?;
+    Ok(())
+}
+
+/// Map a request target to a status code and JSON body.
+fn route(target: &str, store: &dyn CommandStore) -> (u16, String) {
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+    let result = match path {
+        "/commands" => store.list(None).map(|commands| {
+            to_json(&ExportData {
+                version: 1,
+                commands,
+                config: None,
+            })
+        }),
+        "/search" => {
+            let q = query
+                .split('&')
+                .find_map(|pair| pair.strip_prefix("q="))
+                .map(percent_decode)
+                .unwrap_or_default();
+            store.list(None).map(|commands| {
+                let results: Vec<_> = fuzzy_search(&q, &commands)
+                    .into_iter()
+                    .map(|(cmd, _)| cmd)
+                    .collect();
+                to_json(&results)
+            })
+        }
+        _ => match path.strip_prefix("/command/") {
+            Some(cmd_path) => {
+                // Decoding can produce `/etc/passwd` or `../x`; never look outside the store
+                let cmd_path = percent_decode(cmd_path);
+                store::validate_path(&cmd_path)
+                    .and_then(|()| store.get(&cmd_path))
+                    .map(|cmd| to_json(&cmd))
+            }
+            None => return (404, error_body("Unknown endpoint")),
+        },
+    };
+
+    match result {
+        Ok(body) => (200, body),
+        Err(e @ (CmdxError::NotFound(_) | CmdxError::InvalidPath(_))) => (404, error_body(&e.to_string())),
+        Err(e) => (500, error_body(&e.to_string())),
+    }
+}
+
+fn to_json<T: Serialize>(value: &T) -> String {
+    serde_json::to_string(value).unwrap_or_else(|e| error_body(&e.to_string()))
+}
+
+fn error_body(message: &str) -> String {
+    serde_json::json!({ "error": message }).to_string()
+}
+
+/// Decode `%XX` escapes and `+` in a URL component.
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+                match hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                    Some(b) => {
+                        out.push(b);
+                        i += 3;
+                        continue;
+                    }
+                    None => out.push(b'%'),
+                }
+            }
+            b'+' => out.push(b' '),
+            b => out.push(b),
+        }
+        i += 1;
+    }
+
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decodes_query_components() {
+        assert_eq!(percent_decode("docker%2Fprune"), "docker/prune");
+        assert_eq!(percent_decode("git+stash"), "git stash");
+        assert_eq!(percent_decode("100%"), "100%");
+    }
+
+    #[test]
+    fn test_route_stays_inside_store() {
+        let store = store::MemoryStore::default();
+        store.add(&crate::command::Command::new("git/st", "git status", ""), false).unwrap();
+
+        assert_eq!(route("/command/git%2Fst", &store).0, 200);
+        assert_eq!(route("/command/%2Fetc%2Fpasswd", &store).0, 404);
+        assert_eq!(route("/command/..%2Fx", &store).0, 404);
+        assert_eq!(route("/command/..%2F..%2Fetc%2Fhostname", &store).0, 404);
+    }
+}
@@ -1,11 +1,17 @@
+use crate::command::Command;
 use crate::config::Config;
 use crate::error::{CmdxError, Result};
-use crate::store::Store;
-use colored::Colorize;
+use crate::log::is_verbose;
+use crate::store;
+use crate::text::{output_width, print_explanation, wrap_text};
+use colored::{ColoredString, Colorize};
+use std::io::{self, IsTerminal};
 
-pub fn exec(path: String) -> Result<()> {
+use super::copy::copy_command;
+
+pub fn exec(path: String, copy: bool, command_only: bool, explain_only: bool, grep: Option<String>) -> Result<()> {
     let config = Config::load()?;
-    let store = Store::new(&config);
+    let store = store::open(&config)?;
 
     if !store.exists() {
         return Err(CmdxError::NotInitialized);
@@ -13,11 +19,116 @@ pub fn exec(path: String) -> Result<()> {
 
     let cmd = store.get(&path)?;
 
+    // Bare field for scripts: no colors, no layout
+    if command_only {
+        println!("{}", cmd.command);
+    } else if explain_only {
+        if cmd.explanation.is_empty() {
+            return Err(CmdxError::NoExplanation(cmd.path));
+        }
+        println!("{}", cmd.explanation);
+    } else if !io::stdout().is_terminal() {
+        // Piped: one `path<TAB>command` line, like git's porcelain defaults
+        println!("{}\t{}", cmd.path, cmd.command);
+    } else {
+        print_command(&cmd, &config, grep.as_deref());
+    }
+
+    if copy {
+        copy_command(&cmd, &config)?;
+    }
+
+    Ok(())
+}
+
+fn print_command(cmd: &Command, config: &Config, grep: Option<&str>) {
     println!("{}", cmd.path.cyan());
-    println!("{}", cmd.command.white().bold());
+    let Some(term) = grep.filter(|t| !t.is_empty()) else {
+        println!("{}", cmd.command.white().bold());
+        if !cmd.explanation.is_empty() {
+            print_explanation(&cmd.explanation, 0, output_width(&config.display));
+        }
+        print_details(cmd);
+        return;
+    };
+
+    let mut found = find_all(&cmd.command, term).len();
+    println!("{}", highlight(&cmd.command, term, |s| s.white().bold()));
     if !cmd.explanation.is_empty() {
-        println!("{} {}", "→".dimmed(), cmd.explanation.dimmed());
+        found += find_all(&cmd.explanation, term).len();
+        // Wrap first so the highlight codes don't count towards the width
+        let lines = match output_width(&config.display) {
+            Some(w) => wrap_text(&cmd.explanation, w.saturating_sub(2).max(10)),
+            None => vec![cmd.explanation.clone()],
+        };
+        for (i, line) in lines.iter().enumerate() {
+            let lead = if i == 0 { "→".dimmed() } else { " ".normal() };
+            println!("{} {}", lead, highlight(line, term, |s| s.dimmed()));
+        }
     }
+    print_details(cmd);
 
-    Ok(())
+    if found == 0 {
+        eprintln!("{} '{}' does not occur in {}", "!".yellow(), term, cmd.path);
+    }
+}
+
+/// Byte ranges of each non-overlapping, ASCII case-insensitive `term` in `text`.
+fn find_all(text: &str, term: &str) -> Vec<(usize, usize)> {
+    let haystack = text.to_ascii_lowercase();
+    let needle = term.to_ascii_lowercase();
+    let mut ranges = Vec::new();
+    let mut from = 0;
+    while let Some(i) = haystack[from..].find(&needle) {
+        let start = from + i;
+        ranges.push((start, start + needle.len()));
+        from = start + needle.len();
+    }
+    ranges
+}
+
+/// `text` styled with `base`, with each occurrence of `term` picked out.
+fn highlight(text: &str, term: &str, base: impl Fn(&str) -> ColoredString) -> String {
+    let mut out = String::new();
+    let mut last = 0;
+    for (start, end) in find_all(text, term) {
+        out.push_str(&base(&text[last..start]).to_string());
+        out.push_str(&text[start..end].black().on_yellow().bold().to_string());
+        last = end;
+    }
+    out.push_str(&base(&text[last..]).to_string());
+    out
+}
+
+/// The cwd, metadata and example output shown under the explanation.
+fn print_details(cmd: &Command) {
+    if !cmd.cwd.is_empty() {
+        println!("{} {}", "cwd:".dimmed(), cmd.cwd);
+    }
+    // Metadata is noise day to day; -v shows it
+    if is_verbose() {
+        if !cmd.author.is_empty() {
+            println!("{} {}", "author: ".dimmed(), cmd.author);
+        }
+        if !cmd.created.is_empty() {
+            println!("{} {}", "created:".dimmed(), cmd.created);
+        }
+    }
+    if !cmd.output.is_empty() {
+        println!();
+        for line in cmd.output.lines() {
+            println!("  {}", line.dimmed());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_all_is_case_insensitive() {
+        assert_eq!(find_all("rsync -a --Delete src --delete-after", "--delete"), vec![(9, 17), (22, 30)]);
+        assert!(find_all("git status", "push").is_empty());
+    }
 }
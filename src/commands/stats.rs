@@ -0,0 +1,180 @@
+use crate::command::Command;
+use crate::config::Config;
+use crate::error::{CmdxError, Result};
+use crate::history;
+use crate::store::{self, CommandStore};
+use colored::Colorize;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::time::SystemTime;
+
+use super::export::parse_duration;
+use super::empty::print_empty_store;
+
+/// Most-used commands shown in the summary.
+const TOP_LIMIT: usize = 10;
+
+pub fn exec(unused: bool, older_than: Option<String>, export: bool, format: String) -> Result<()> {
+    let config = Config::load()?;
+    let store = store::open_filesystem(&config)?;
+
+    if !store.exists() {
+        return Err(CmdxError::NotInitialized);
+    }
+
+    let commands = store.list(None)?;
+    if commands.is_empty() {
+        print_empty_store();
+        return Ok(());
+    }
+    let counts = history::use_counts();
+
+    if export {
+        let rows = usage_rows(&commands, &counts, &history::last_used());
+        return print_usage(&rows, &format);
+    }
+
+    if unused {
+        let cutoff = match older_than {
+            Some(age) => Some(
+                SystemTime::now()
+                    .checked_sub(parse_duration(&age)?)
+                    .unwrap_or(SystemTime::UNIX_EPOCH),
+            ),
+            None => None,
+        };
+
+        // Bare paths, one per line, so the list can be piped into `cmdx rm`
+        for cmd in &commands {
+            if counts.contains_key(&cmd.path) {
+                continue;
+            }
+            if let Some(cutoff) = cutoff {
+                if !store.modified(&cmd.path).map(|t| t < cutoff).unwrap_or(false) {
+                    continue;
+                }
+            }
+            println!("{}", cmd.path);
+        }
+        return Ok(());
+    }
+
+    let used = commands.iter().filter(|c| counts.contains_key(&c.path)).count();
+    let runs: usize = counts.values().sum();
+
+    println!("{:<10} {}", "Commands".dimmed(), commands.len());
+    println!("{:<10} {}", "Runs".dimmed(), runs);
+    println!("{:<10} {}", "Never run".dimmed(), commands.len() - used);
+
+    let mut top: Vec<(&String, &usize)> = counts
+        .iter()
+        .filter(|(path, _)| commands.iter().any(|c| &c.path == *path))
+        .collect();
+    top.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+
+    if !top.is_empty() {
+        println!();
+        for (path, count) in top.into_iter().take(TOP_LIMIT) {
+            println!("{:>5}  {}", count, path.cyan());
+        }
+    }
+
+    Ok(())
+}
+
+/// One line of `stats --export`.
+#[derive(Debug, Serialize)]
+struct UsageRow {
+    path: String,
+    runs: usize,
+    /// Last run, `YYYY-MM-DD HH:MM:SS` UTC; empty if never run.
+    last_used: String,
+    created: String,
+    /// Run in the past but no longer in the store (removed or renamed).
+    stale: bool,
+}
+
+/// Every stored command with its usage, then history-only paths as stale.
+fn usage_rows(commands: &[Command], counts: &HashMap<String, usize>, last: &HashMap<String, u64>) -> Vec<UsageRow> {
+    let last_used = |path: &str| last.get(path).map(|&t| history::format_timestamp(t)).unwrap_or_default();
+
+    let mut rows: Vec<UsageRow> = commands
+        .iter()
+        .map(|cmd| UsageRow {
+            path: cmd.path.clone(),
+            runs: counts.get(&cmd.path).copied().unwrap_or(0),
+            last_used: last_used(&cmd.path),
+            created: cmd.created.clone(),
+            stale: false,
+        })
+        .collect();
+
+    let stale: BTreeMap<&String, &usize> = counts
+        .iter()
+        .filter(|(path, _)| !commands.iter().any(|c| &c.path == *path))
+        .collect();
+    rows.extend(stale.into_iter().map(|(path, &runs)| UsageRow {
+        path: path.clone(),
+        runs,
+        last_used: last_used(path),
+        created: String::new(),
+        stale: true,
+    }));
+    rows
+}
+
+fn print_usage(rows: &[UsageRow], format: &str) -> Result<()> {
+    if format == "json" {
+        let out = serde_json::to_string_pretty(rows)
+            .map_err(|e| CmdxError::Config(format!("Failed to serialize: {}", e)))?;
+        println!("{}", out);
+        return Ok(());
+    }
+
+    println!("path,runs,last_used,created,stale");
+    for row in rows {
+        println!(
+            "{},{},{},{},{}",
+            csv_field(&row.path),
+            row.runs,
+            row.last_used,
+            row.created,
+            row.stale
+        );
+    }
+    Ok(())
+}
+
+/// Quote a CSV field when it contains a separator, quote or newline.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usage_rows_flag_stale_paths() {
+        let commands = vec![Command::new("git/log", "git log", ""), Command::new("git/st", "git status", "")];
+        let counts = HashMap::from([("git/log".to_string(), 3), ("old/gone".to_string(), 1)]);
+        let last = HashMap::from([("git/log".to_string(), 0), ("old/gone".to_string(), 0)]);
+
+        let rows = usage_rows(&commands, &counts, &last);
+        let summary: Vec<_> = rows.iter().map(|r| (r.path.as_str(), r.runs, r.stale)).collect();
+        assert_eq!(summary, vec![("git/log", 3, false), ("git/st", 0, false), ("old/gone", 1, true)]);
+        assert_eq!(rows[0].last_used, "1970-01-01 00:00:00");
+        assert!(rows[1].last_used.is_empty());
+    }
+
+    #[test]
+    fn test_csv_field() {
+        assert_eq!(csv_field("git/log"), "git/log");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+}
@@ -0,0 +1,55 @@
+use crate::command::Command;
+use crate::error::{CmdxError, Result};
+use colored::Colorize;
+
+use super::export::ExportData;
+
+/// Built-in templates, kept separate from the user's store.
+const TEMPLATES_JSON: &str = include_str!("templates.json");
+
+pub fn exec() -> Result<()> {
+    for template in builtin()? {
+        println!("{:<20} {}", template.path.cyan(), template.command.white());
+        if !template.explanation.is_empty() {
+            println!("{:<20} {} {}", "", "→".dimmed(), template.explanation.dimmed());
+        }
+    }
+
+    println!();
+    println!(
+        "{}",
+        "Add one with: cmdx add <path> --from <template>".dimmed()
+    );
+    Ok(())
+}
+
+pub fn builtin() -> Result<Vec<Command>> {
+    let data: ExportData = serde_json::from_str(TEMPLATES_JSON)
+        .map_err(|e| CmdxError::Config(format!("Invalid built-in templates: {}", e)))?;
+    Ok(data.commands)
+}
+
+pub fn find_template(name: &str) -> Result<Command> {
+    builtin()?
+        .into_iter()
+        .find(|t| t.path == name)
+        .ok_or_else(|| CmdxError::NotFound(format!("template '{}' (see 'cmdx templates')", name)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_templates_parse() {
+        let templates = builtin().unwrap();
+        assert!(!templates.is_empty());
+        assert!(templates.iter().all(|t| !t.command.is_empty()));
+    }
+
+    #[test]
+    fn test_find_template() {
+        assert_eq!(find_template("docker-cleanup").unwrap().command, "docker system prune -af --volumes");
+        assert!(find_template("nope").is_err());
+    }
+}
@@ -0,0 +1,87 @@
+use crate::config::Config;
+use crate::error::{CmdxError, Result};
+use crate::history::format_timestamp;
+use crate::log::success;
+use crate::store::{self, CommandStore};
+use crate::trash::Trash;
+use colored::Colorize;
+use std::io::{self, Write};
+
+use super::confirm::{choices, read_yes};
+
+/// `cmdx trash`: list removed commands, newest first.
+pub fn list() -> Result<()> {
+    let entries = Trash::new().list()?;
+
+    if entries.is_empty() {
+        println!("{}", "Trash is empty.".dimmed());
+        return Ok(());
+    }
+
+    for (i, entry) in entries.iter().enumerate() {
+        println!(
+            "{:>3}  {}  {}",
+            i + 1,
+            format_timestamp(entry.deleted_at).dimmed(),
+            entry.command.path.cyan()
+        );
+    }
+
+    Ok(())
+}
+
+/// `cmdx undo [n]`: restore the n-th entry of `cmdx trash` to its original path.
+pub fn undo(n: usize, force: bool) -> Result<()> {
+    let config = Config::load()?;
+    let store = store::open_filesystem(&config)?;
+
+    if !store.exists() {
+        return Err(CmdxError::NotInitialized);
+    }
+
+    let trash = Trash::new();
+    let entry = trash
+        .list()?
+        .into_iter()
+        .nth(n.saturating_sub(1))
+        .ok_or_else(|| CmdxError::NotFound(format!("trash entry {}", n)))?;
+
+    // Check before taking it out of the trash so nothing is lost on conflict
+    let file_path = store.command_path(&entry.command.path);
+    if file_path.exists() && !force {
+        return Err(CmdxError::AlreadyExists(file_path));
+    }
+
+    let entry = trash.take(n)?;
+    store.add(&entry.command, force)?;
+    success!("Restored {}", entry.command.path.cyan());
+
+    Ok(())
+}
+
+/// `cmdx clean-trash`: permanently delete trashed commands.
+pub fn clean(force: bool) -> Result<()> {
+    let trash = Trash::new();
+    let count = trash.list()?.len();
+
+    if count == 0 {
+        println!("{}", "Trash is empty.".dimmed());
+        return Ok(());
+    }
+
+    if !force {
+        let default_yes = Config::load()?.core.confirm_default;
+        print!("Permanently delete {} trashed command(s)? {} ", count, choices(default_yes));
+        io::stdout().flush()?;
+
+        if !read_yes(default_yes)? {
+            println!("{}", "Cancelled.".dimmed());
+            return Ok(());
+        }
+    }
+
+    let removed = trash.clear()?;
+    success!("Deleted {} trashed command(s)", removed);
+
+    Ok(())
+}
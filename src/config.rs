@@ -1,9 +1,13 @@
+use crate::command::Command;
 use crate::error::{CmdxError, Result};
+use crate::log::verbose;
 use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Config {
     #[serde(default)]
     pub core: CoreConfig,
@@ -11,9 +15,17 @@ pub struct Config {
     pub display: DisplayConfig,
     #[serde(default)]
     pub clipboard: ClipboardConfig,
+    #[serde(default)]
+    pub actions: ActionsConfig,
+    #[serde(default)]
+    pub run: RunConfig,
+    #[serde(default)]
+    pub lint: LintConfig,
+    #[serde(default)]
+    pub safety: SafetyConfig,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CoreConfig {
     #[serde(default = "default_store_path")]
     pub store_path: String,
@@ -21,26 +33,150 @@ pub struct CoreConfig {
     pub default_action: String,
     #[serde(default = "default_shell")]
     pub shell: String,
+    #[serde(default)]
+    pub default_explanation: String,
+    /// Whether Enter at a `run --confirm` / `rm` prompt means yes
+    #[serde(default)]
+    pub confirm_default: bool,
+    /// Recorded as the author of added commands; defaults to $USER
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub author: String,
+    /// Omit the explanation line when it is empty (store format v2)
+    #[serde(default)]
+    pub compact_files: bool,
+    /// Remove category directories left empty by rm/mv
+    #[serde(default = "default_true")]
+    pub prune_empty_dirs: bool,
+    /// Expand `~` and `$VARS` in command text for copy and run previews
+    #[serde(default)]
+    pub expand_paths: bool,
+    /// How `add` joins multi-line commands: "semicolon" or "continuation"
+    #[serde(default = "default_newline_join")]
+    pub newline_join: String,
+    /// Where commands are kept; only "filesystem" exists so far
+    #[serde(default = "default_backend")]
+    pub backend: String,
+    /// Largest command (with explanation and output) add/import accept; 0 disables
+    #[serde(default = "default_max_command_bytes")]
+    pub max_command_bytes: usize,
+    /// Let exact-path lookups match `Docker/Prune` to `docker/prune`
+    #[serde(default)]
+    pub case_insensitive_paths: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DisplayConfig {
     #[serde(default = "default_true")]
     pub color: bool,
     #[serde(default = "default_tree_style")]
     pub tree_style: String,
+    #[serde(default = "default_icons")]
+    pub icons: String,
+    #[serde(default = "default_highlighters")]
+    pub highlighters: Vec<String>,
+    #[serde(default = "default_true")]
+    pub wrap: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wrap_width: Option<usize>,
+    /// TUI list row layout, e.g. "{icon} {path:30} {command}"
+    #[serde(default = "default_list_format")]
+    pub list_format: String,
+    /// Color high-risk commands red in `cmdx ls`
+    #[serde(default)]
+    pub risk_colors: bool,
+    /// Sibling order in `cmdx ls`: "alphabetical" or "manual" (`order:` meta)
+    #[serde(default = "default_order")]
+    pub order: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ActionsConfig {
+    /// Per-category default action, keyed by path prefix (e.g. "k8s" = "show")
+    #[serde(default)]
+    pub overrides: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunConfig {
+    /// Environment variables set for every `cmdx run`
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// Arguments passed to the shell before the command, e.g. ["-lc"]
+    #[serde(default = "default_shell_args")]
+    pub shell_args: Vec<String>,
+}
+
+impl Default for RunConfig {
+    fn default() -> Self {
+        Self {
+            env: BTreeMap::new(),
+            shell_args: default_shell_args(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LintConfig {
+    #[serde(default = "default_true")]
+    pub lowercase_paths: bool,
+    #[serde(default = "default_true")]
+    pub no_spaces: bool,
+    #[serde(default = "default_true")]
+    pub require_explanation: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_command_length: Option<usize>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SafetyConfig {
+    /// Text of the `run` confirmation; `{path}` and `{command}` are filled in
+    #[serde(default = "default_confirm_prompt")]
+    pub confirm_prompt: String,
+    /// Path prefixes whose commands must be confirmed by retyping their path
+    #[serde(default)]
+    pub type_to_confirm: Vec<String>,
+    /// Also require retyping for commands matching a dangerous pattern
+    #[serde(default)]
+    pub type_to_confirm_dangerous: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClipboardConfig {
     #[serde(default = "default_clipboard_tool")]
     pub tool: String,
 }
 
+thread_local! {
+    /// Set for the length of a `cmdx repl` session so each line reuses it.
+    static SESSION: RefCell<Option<Config>> = const { RefCell::new(None) };
+}
+
+/// Make [`Config::load`] return `config` (or read the file again for `None`).
+pub fn set_session(config: Option<Config>) {
+    SESSION.with(|session| *session.borrow_mut() = config);
+}
+
 fn default_store_path() -> String {
     "~/.config/cmdx/store".to_string()
 }
 
+fn expand_store_path(raw: &str, home: Option<&str>) -> Result<PathBuf> {
+    let expanded = shellexpand::tilde_with_context(raw, || home);
+    verbose!("store path {} -> {}", raw, expanded);
+
+    let path = PathBuf::from(expanded.as_ref());
+    if !path.is_absolute() {
+        return Err(CmdxError::Config(format!(
+            "core.store_path '{}' expands to '{}', which is not an absolute path. \
+             Set $HOME or use an absolute store_path in {}",
+            raw,
+            expanded,
+            Config::config_path().display()
+        )));
+    }
+    Ok(path)
+}
+
 fn default_action() -> String {
     "copy".to_string()
 }
@@ -49,6 +185,56 @@ fn default_shell() -> String {
     "bash".to_string()
 }
 
+fn default_newline_join() -> String {
+    "semicolon".to_string()
+}
+
+fn default_order() -> String {
+    "alphabetical".to_string()
+}
+
+fn default_shell_args() -> Vec<String> {
+    vec!["-c".to_string()]
+}
+
+fn default_backend() -> String {
+    "filesystem".to_string()
+}
+
+fn default_max_command_bytes() -> usize {
+    64 * 1024
+}
+
+fn default_confirm_prompt() -> String {
+    "Execute?".to_string()
+}
+
+/// Whether `path` is `prefix` itself or lies under it.
+fn under_prefix(path: &str, prefix: &str) -> bool {
+    let prefix = prefix.trim_end_matches('/');
+    path == prefix || path.starts_with(&format!("{}/", prefix))
+}
+
+/// Replace `~` with `home` wherever it starts a word and is followed by `/` or the word's end.
+fn expand_tildes(text: &str, home: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut prev: Option<char> = None;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let word_start = prev.is_none_or(|p| p.is_whitespace() || p == '=' || p == ':');
+        let word_end = chars.peek().is_none_or(|n| *n == '/' || n.is_whitespace());
+        if c == '~' && word_start && word_end {
+            out.push_str(home);
+        } else {
+            out.push(c);
+        }
+        prev = Some(c);
+    }
+
+    out
+}
+
 fn default_true() -> bool {
     true
 }
@@ -57,6 +243,18 @@ fn default_tree_style() -> String {
     "unicode".to_string()
 }
 
+fn default_icons() -> String {
+    "nerd".to_string()
+}
+
+fn default_list_format() -> String {
+    "{icon} {path}".to_string()
+}
+
+fn default_highlighters() -> Vec<String> {
+    vec!["bat".to_string()]
+}
+
 fn default_clipboard_tool() -> String {
     "auto".to_string()
 }
@@ -67,6 +265,16 @@ impl Default for CoreConfig {
             store_path: default_store_path(),
             default_action: default_action(),
             shell: default_shell(),
+            default_explanation: String::new(),
+            confirm_default: false,
+            author: String::new(),
+            compact_files: false,
+            prune_empty_dirs: true,
+            expand_paths: false,
+            newline_join: default_newline_join(),
+            backend: default_backend(),
+            max_command_bytes: default_max_command_bytes(),
+            case_insensitive_paths: false,
         }
     }
 }
@@ -76,29 +284,72 @@ impl Default for DisplayConfig {
         Self {
             color: true,
             tree_style: default_tree_style(),
+            icons: default_icons(),
+            highlighters: default_highlighters(),
+            wrap: true,
+            wrap_width: None,
+            list_format: default_list_format(),
+            risk_colors: false,
+            order: default_order(),
         }
     }
 }
 
-impl Default for ClipboardConfig {
+impl Default for LintConfig {
     fn default() -> Self {
         Self {
-            tool: default_clipboard_tool(),
+            lowercase_paths: true,
+            no_spaces: true,
+            require_explanation: true,
+            max_command_length: None,
         }
     }
 }
 
-impl Default for Config {
+impl Default for SafetyConfig {
     fn default() -> Self {
         Self {
-            core: CoreConfig::default(),
-            display: DisplayConfig::default(),
-            clipboard: ClipboardConfig::default(),
+            confirm_prompt: default_confirm_prompt(),
+            type_to_confirm: Vec::new(),
+            type_to_confirm_dangerous: false,
+        }
+    }
+}
+
+impl Default for ClipboardConfig {
+    fn default() -> Self {
+        Self {
+            tool: default_clipboard_tool(),
         }
     }
 }
 
 impl Config {
+    /// `core.author`, falling back to the login name.
+    pub fn author(&self) -> String {
+        if !self.core.author.is_empty() {
+            return self.core.author.clone();
+        }
+        std::env::var("USER")
+            .or_else(|_| std::env::var("USERNAME"))
+            .unwrap_or_default()
+    }
+
+    /// Command text as it will be copied or previewed: with `core.expand_paths`
+    /// set, `~` and `$VARS` are expanded. Unset variables are left as written.
+    pub fn display_command(&self, command: &str) -> String {
+        if !self.core.expand_paths {
+            return command.to_string();
+        }
+        let expanded = shellexpand::env_with_context_no_errors(command, |var| std::env::var(var).ok());
+
+        // shellexpand only handles a leading `~`; a shell expands it per word
+        match dirs::home_dir() {
+            Some(home) => expand_tildes(&expanded, &home.to_string_lossy()),
+            None => expanded.into_owned(),
+        }
+    }
+
     pub fn config_dir() -> PathBuf {
         dirs::config_dir()
             .unwrap_or_else(|| PathBuf::from("~/.config"))
@@ -109,20 +360,56 @@ impl Config {
         Self::config_dir().join("config.toml")
     }
 
+    /// The config file, or the one pinned for a `cmdx repl` session.
     pub fn load() -> Result<Self> {
+        if let Some(config) = SESSION.with(|session| session.borrow().clone()) {
+            return Ok(config);
+        }
+
         let path = Self::config_path();
 
         if !path.exists() {
+            verbose!("config {} not found, using defaults", path.display());
             return Ok(Self::default());
         }
 
+        verbose!("loading config from {}", path.display());
         let content = fs::read_to_string(&path)?;
         toml::from_str(&content).map_err(|e| CmdxError::Config(e.to_string()))
     }
 
-    pub fn store_path(&self) -> PathBuf {
-        let expanded = shellexpand::tilde(&self.core.store_path);
-        PathBuf::from(expanded.as_ref())
+    /// Action for direct path access: the longest matching category override,
+    /// else `core.default_action`.
+    pub fn action_for(&self, path: &str) -> &str {
+        self.actions
+            .overrides
+            .iter()
+            .filter(|(prefix, _)| under_prefix(path, prefix))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, action)| action.as_str())
+            .unwrap_or(&self.core.default_action)
+    }
+
+    /// `safety.confirm_prompt` with `{path}` and `{command}` filled in.
+    pub fn confirm_prompt(&self, cmd: &Command) -> String {
+        self.safety
+            .confirm_prompt
+            .replace("{path}", &cmd.path)
+            .replace("{command}", &cmd.command)
+    }
+
+    /// Whether running `cmd` needs its path retyped instead of a y/N answer.
+    pub fn requires_typed_confirm(&self, cmd: &Command) -> bool {
+        self.safety.type_to_confirm.iter().any(|prefix| under_prefix(&cmd.path, prefix))
+            || (self.safety.type_to_confirm_dangerous && cmd.is_dangerous())
+    }
+
+    /// `core.store_path` with `~` expanded. Fails if the result is not
+    /// absolute (e.g. `$HOME` is unset), which would otherwise put the store
+    /// somewhere relative to the current directory.
+    pub fn store_path(&self) -> Result<PathBuf> {
+        let home = dirs::home_dir().map(|h| h.to_string_lossy().into_owned());
+        expand_store_path(&self.core.store_path, home.as_deref())
     }
 
     pub fn save_default() -> Result<()> {
@@ -140,3 +427,58 @@ impl Config {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store_path_needs_home_for_tilde() {
+        assert_eq!(expand_store_path("~/.cmdx", Some("/home/me")).unwrap(), PathBuf::from("/home/me/.cmdx"));
+        assert_eq!(expand_store_path("/srv/cmdx", None).unwrap(), PathBuf::from("/srv/cmdx"));
+
+        // No home directory: `~` stays literal and the path is relative
+        let err = expand_store_path("~/.config/cmdx/store", None).unwrap_err();
+        assert!(matches!(err, CmdxError::Config(ref msg) if msg.contains("not an absolute path")));
+        assert!(expand_store_path("store", Some("/home/me")).is_err());
+    }
+
+    #[test]
+    fn test_action_for_longest_prefix() {
+        let mut config = Config::default();
+        config.actions.overrides.insert("k8s".to_string(), "show".to_string());
+        config.actions.overrides.insert("k8s/logs".to_string(), "run".to_string());
+
+        assert_eq!(config.action_for("k8s/pods"), "show");
+        assert_eq!(config.action_for("k8s/logs/tail"), "run");
+        assert_eq!(config.action_for("k8sx/pods"), "copy");
+        assert_eq!(config.action_for("git/status"), "copy");
+    }
+
+    #[test]
+    fn test_typed_confirm_and_prompt() {
+        let mut config = Config::default();
+        let deploy = Command::new("prod/deploy", "make deploy", "");
+        let wipe = Command::new("db/wipe", "psql -c 'DROP TABLE users'", "");
+
+        assert!(!config.requires_typed_confirm(&deploy));
+        assert_eq!(config.confirm_prompt(&deploy), "Execute?");
+
+        config.safety.type_to_confirm = vec!["prod/".to_string()];
+        config.safety.confirm_prompt = "PRODUCTION {path}:".to_string();
+        assert!(config.requires_typed_confirm(&deploy));
+        assert!(!config.requires_typed_confirm(&Command::new("production/x", "true", "")));
+        assert_eq!(config.confirm_prompt(&deploy), "PRODUCTION prod/deploy:");
+
+        assert!(!config.requires_typed_confirm(&wipe));
+        config.safety.type_to_confirm_dangerous = true;
+        assert!(config.requires_typed_confirm(&wipe));
+    }
+
+    #[test]
+    fn test_expand_tildes() {
+        assert_eq!(expand_tildes("backup ~/data", "/home/u"), "backup /home/u/data");
+        assert_eq!(expand_tildes("~ --dir=~/x", "/h"), "/h --dir=/h/x");
+        assert_eq!(expand_tildes("echo a~b ~user", "/h"), "echo a~b ~user");
+    }
+}
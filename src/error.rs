@@ -18,6 +18,12 @@ pub enum CmdxError {
     #[error("Store not initialized. Run 'cmdx init' first.")]
     NotInitialized,
 
+    #[error("Store path {0} is not a directory. Move the file away or set core.store_path in config.toml.")]
+    StoreNotDirectory(PathBuf),
+
+    #[error("Store at {0} is not readable ({1}). Check its permissions.")]
+    StoreUnreadable(PathBuf, std::io::Error),
+
     #[error("Config error: {0}")]
     Config(String),
 
@@ -27,8 +33,45 @@ pub enum CmdxError {
     #[error("Command execution failed: {0}")]
     Execution(String),
 
+    #[error("{0} has no explanation")]
+    NoExplanation(String),
+
+    #[error("No command has been run yet; nothing to rerun")]
+    NoHistory,
+
+    #[error("No command has been copied yet")]
+    NothingCopied,
+
+    #[error("Working directory {0} does not exist")]
+    MissingCwd(PathBuf),
+
+    #[error("Interrupted")]
+    Interrupted,
+
+    #[error("{0} lint issue(s) found")]
+    LintFailed(usize),
+
+    #[error("{0} invalid import entry(s) found")]
+    ImportInvalid(usize),
+
+    #[error("{path} is {size} bytes, over the {limit}-byte limit (core.max_command_bytes)")]
+    CommandTooLarge { path: String, size: usize, limit: usize },
+
+    #[error("No such subcommand: {0}")]
+    UnknownSubcommand(String),
+
     #[error("TUI error: {0}")]
     Tui(String),
 }
 
+impl CmdxError {
+    /// Process exit code for this error (130 for SIGINT, like shells).
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            CmdxError::Interrupted => 130,
+            _ => 1,
+        }
+    }
+}
+
 pub type Result<T> = std::result::Result<T, CmdxError>;
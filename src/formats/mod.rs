@@ -0,0 +1,158 @@
+//! Converters from other snippet managers' files to [`Command`]s.
+//!
+//! None of these formats have cmdx's hierarchy, so paths are built from the
+//! first tag (or page name) plus a slug of the description.
+
+mod navi;
+mod pet;
+mod tldr;
+
+use crate::command::Command;
+use crate::error::{CmdxError, Result};
+use std::collections::HashSet;
+
+/// Source formats accepted by `import --from`.
+pub const FORMATS: &[&str] = &["pet", "navi", "tldr"];
+
+/// Words kept from a description when it becomes a path segment.
+const SLUG_WORDS: usize = 5;
+
+/// Commands converted from a foreign file, plus entries that couldn't be.
+#[derive(Debug, Default)]
+pub struct Conversion {
+    pub commands: Vec<Command>,
+    /// One line per entry that was dropped, saying why.
+    pub unmapped: Vec<String>,
+}
+
+/// Convert `content` written in `format` (one of [`FORMATS`]).
+pub fn convert(format: &str, content: &str) -> Result<Conversion> {
+    let drafts = match format {
+        "pet" => pet::parse(content)?,
+        "navi" => navi::parse(content),
+        "tldr" => tldr::parse(content),
+        other => {
+            return Err(CmdxError::Config(format!(
+                "Unknown import format '{}' (expected one of: {})",
+                other,
+                FORMATS.join(", ")
+            )))
+        }
+    };
+
+    let mut conversion = Conversion::default();
+    let mut taken = HashSet::new();
+    for draft in drafts {
+        let command = draft.command.trim();
+        if command.is_empty() {
+            conversion.unmapped.push(format!("{}: no command", label(&draft.description)));
+            continue;
+        }
+        let Some(path) = unique_path(&draft, &mut taken) else {
+            conversion.unmapped.push(format!("{}: no description or tag to name it by", command));
+            continue;
+        };
+        conversion.commands.push(Command::new(path, command, draft.description.trim()));
+    }
+    Ok(conversion)
+}
+
+/// A snippet as read from the source, before it has a path.
+#[derive(Debug, Default)]
+struct Draft {
+    command: String,
+    description: String,
+    /// Folder candidates, most specific first; only the first is used.
+    tags: Vec<String>,
+}
+
+/// `tag/description-slug`, suffixed `-2`, `-3`... when already taken.
+fn unique_path(draft: &Draft, taken: &mut HashSet<String>) -> Option<String> {
+    let dir = draft.tags.iter().map(|t| slugify(t, 1)).find(|s| !s.is_empty());
+    let name = slugify(&draft.description, SLUG_WORDS);
+
+    let base = match (dir, name.is_empty()) {
+        (Some(dir), false) => format!("{}/{}", dir, name),
+        (Some(dir), true) => format!("{}/{}", dir, slugify(&draft.command, 2)),
+        (None, false) => name,
+        (None, true) => return None,
+    };
+
+    let mut path = base.clone();
+    let mut n = 2;
+    while !taken.insert(path.clone()) {
+        path = format!("{}-{}", base, n);
+        n += 1;
+    }
+    Some(path)
+}
+
+/// Lowercase, dash-joined first `words` alphanumeric words of `text`.
+fn slugify(text: &str, words: usize) -> String {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .take(words)
+        .map(|w| w.to_lowercase())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+fn label(description: &str) -> &str {
+    if description.trim().is_empty() {
+        "(untitled)"
+    } else {
+        description.trim()
+    }
+}
+
+/// Rewrite `open`name`close` placeholders as cmdx's `{name}`, dropping any
+/// `=default` suffix (pet writes `<name=default>`).
+fn to_placeholders(command: &str, open: &str, close: &str) -> String {
+    let mut out = String::new();
+    let mut rest = command;
+    while let Some(start) = rest.find(open) {
+        let after = &rest[start + open.len()..];
+        let Some(end) = after.find(close) else { break };
+        let inner = &after[..end];
+        let name = inner.split('=').next().unwrap_or("").trim();
+        out.push_str(&rest[..start]);
+        if !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-') {
+            out.push('{');
+            out.push_str(name);
+            out.push('}');
+        } else {
+            // Not a placeholder (e.g. a `<` redirect); keep it verbatim
+            out.push_str(&rest[start..start + open.len() + end + close.len()]);
+        }
+        rest = &after[end + close.len()..];
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unique_path() {
+        let mut taken = HashSet::new();
+        let draft = Draft {
+            command: "git log --oneline".to_string(),
+            description: "Show compact history, one line each".to_string(),
+            tags: vec!["Git".to_string()],
+        };
+        assert_eq!(unique_path(&draft, &mut taken).unwrap(), "git/show-compact-history-one-line");
+        assert_eq!(unique_path(&draft, &mut taken).unwrap(), "git/show-compact-history-one-line-2");
+
+        let bare = Draft { command: "ls".to_string(), ..Default::default() };
+        assert!(unique_path(&bare, &mut taken).is_none());
+    }
+
+    #[test]
+    fn test_to_placeholders() {
+        assert_eq!(to_placeholders("ssh <user=root>@<host>", "<", ">"), "ssh {user}@{host}");
+        assert_eq!(to_placeholders("sort < in.txt > out.txt", "<", ">"), "sort < in.txt > out.txt");
+        assert_eq!(to_placeholders("tar xf {{archive}}", "{{", "}}"), "tar xf {archive}");
+    }
+}
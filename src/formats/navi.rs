@@ -0,0 +1,80 @@
+//! navi `.cheat` files: `% tags` headers, `# description` lines each
+//! followed by a command, `$ var: ...` suggestion lines and `;` comments.
+
+use super::{to_placeholders, Draft};
+
+pub(super) fn parse(content: &str) -> Vec<Draft> {
+    let mut drafts = Vec::new();
+    let mut tags: Vec<String> = Vec::new();
+    let mut description = String::new();
+    let mut current: Option<Draft> = None;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if let Some(rest) = trimmed.strip_prefix('%') {
+            drafts.extend(current.take());
+            tags = rest.split(',').map(|t| t.trim().to_string()).filter(|t| !t.is_empty()).collect();
+        } else if let Some(rest) = trimmed.strip_prefix('#') {
+            drafts.extend(current.take());
+            description = rest.trim().to_string();
+        } else if trimmed.is_empty() {
+            drafts.extend(current.take());
+        } else if trimmed.starts_with('$') || trimmed.starts_with(';') || trimmed.starts_with('@') {
+            // Variable sources, comments and `@ extends` have no cmdx equivalent
+            drafts.extend(current.take());
+        } else {
+            match &mut current {
+                // cmdx commands are one line: fold `\` continuations, and
+                // chain further lines the way `[core] newline_join` defaults
+                Some(draft) => match draft.command.strip_suffix('\\') {
+                    Some(head) => draft.command = format!("{} {}", head.trim_end(), trimmed),
+                    None => {
+                        draft.command.push_str("; ");
+                        draft.command.push_str(trimmed);
+                    }
+                },
+                None => {
+                    current = Some(Draft {
+                        command: String::new(),
+                        description: std::mem::take(&mut description),
+                        tags: tags.clone(),
+                    });
+                    current.as_mut().unwrap().command.push_str(trimmed);
+                }
+            }
+        }
+    }
+    drafts.extend(current);
+
+    for draft in &mut drafts {
+        draft.command = to_placeholders(&draft.command, "<", ">");
+    }
+    drafts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_navi() {
+        let content = "\
+% git, code
+
+# Change branch
+git checkout <branch>
+
+$ branch: git branch | awk '{print $NF}'
+
+# Count lines
+wc -l \\
+  <file>
+";
+        let drafts = parse(content);
+        assert_eq!(drafts.len(), 2);
+        assert_eq!(drafts[0].command, "git checkout {branch}");
+        assert_eq!(drafts[0].description, "Change branch");
+        assert_eq!(drafts[0].tags, ["git", "code"]);
+        assert_eq!(drafts[1].command, "wc -l {file}");
+    }
+}
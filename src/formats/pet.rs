@@ -0,0 +1,61 @@
+//! pet's `snippet.toml`: an array of `[[snippets]]` tables.
+
+use super::{to_placeholders, Draft};
+use crate::error::{CmdxError, Result};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct PetFile {
+    #[serde(default)]
+    snippets: Vec<Snippet>,
+}
+
+#[derive(Deserialize)]
+struct Snippet {
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    command: String,
+    #[serde(default)]
+    tag: Vec<String>,
+}
+
+pub(super) fn parse(content: &str) -> Result<Vec<Draft>> {
+    let file: PetFile =
+        toml::from_str(content).map_err(|e| CmdxError::Config(format!("Invalid pet snippets: {}", e)))?;
+
+    Ok(file
+        .snippets
+        .into_iter()
+        .map(|s| Draft {
+            command: to_placeholders(&s.command, "<", ">"),
+            description: s.description,
+            tags: s.tag,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pet() {
+        let content = r#"
+[[snippets]]
+  description = "Connect to a host"
+  command = "ssh <user=root>@<host>"
+  tag = ["ssh", "remote"]
+  output = ""
+
+[[snippets]]
+  description = "Disk usage"
+  command = "df -h"
+"#;
+        let drafts = parse(content).unwrap();
+        assert_eq!(drafts.len(), 2);
+        assert_eq!(drafts[0].command, "ssh {user}@{host}");
+        assert_eq!(drafts[0].tags, ["ssh", "remote"]);
+        assert!(drafts[1].tags.is_empty());
+    }
+}
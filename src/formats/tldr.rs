@@ -0,0 +1,68 @@
+//! tldr pages: a `# name` title, then `- description:` items each followed
+//! by a backticked example.
+
+use super::{to_placeholders, Draft};
+
+pub(super) fn parse(content: &str) -> Vec<Draft> {
+    let mut drafts = Vec::new();
+    let mut page = String::new();
+    let mut description = String::new();
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if let Some(title) = trimmed.strip_prefix("# ") {
+            page = title.trim().to_string();
+        } else if let Some(item) = trimmed.strip_prefix("- ") {
+            description = item.trim().trim_end_matches(':').to_string();
+        } else if let Some(example) = trimmed.strip_prefix('`').and_then(|l| l.strip_suffix('`')) {
+            drafts.push(Draft {
+                command: to_placeholders(&name_placeholders(example), "{{", "}}"),
+                description: std::mem::take(&mut description),
+                tags: vec![page.clone()],
+            });
+        }
+    }
+    drafts
+}
+
+/// tldr placeholders are free text (`{{path/to/file}}`); make them names.
+fn name_placeholders(example: &str) -> String {
+    let mut out = String::new();
+    let mut rest = example;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else { break };
+        let inner = &rest[start + 2..start + 2 + len];
+        let name: Vec<_> = inner
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .collect();
+        out.push_str(&rest[..start]);
+        out.push_str(&format!("{{{{{}}}}}", name.join("_")));
+        rest = &rest[start + 4 + len..];
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tldr() {
+        let content = "\
+# tar
+
+> Archiving utility.
+
+- Create an archive from files:
+
+`tar cf {{target.tar}} {{path/to/file1}}`
+";
+        let drafts = parse(content);
+        assert_eq!(drafts.len(), 1);
+        assert_eq!(drafts[0].command, "tar cf {target_tar} {path_to_file1}");
+        assert_eq!(drafts[0].description, "Create an archive from files");
+        assert_eq!(drafts[0].tags, ["tar"]);
+    }
+}
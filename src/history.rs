@@ -0,0 +1,248 @@
+use crate::config::Config;
+use crate::error::{CmdxError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// One execution of a stored command, stored as a JSON line.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub path: String,
+    pub command: String,
+    pub timestamp: u64,
+    pub exit_code: i32,
+}
+
+impl HistoryEntry {
+    pub fn new(path: &str, command: &str, exit_code: i32) -> Self {
+        Self {
+            path: path.to_string(),
+            command: command.to_string(),
+            timestamp: now(),
+            exit_code,
+        }
+    }
+}
+
+/// Current unix time in seconds.
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+pub fn history_path() -> PathBuf {
+    Config::config_dir().join("history.jsonl")
+}
+
+pub fn append(entry: &HistoryEntry) -> Result<()> {
+    let path = history_path();
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let line = serde_json::to_string(entry)
+        .map_err(|e| CmdxError::Config(e.to_string()))?;
+
+    let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+    writeln!(file, "{}", line)?;
+    Ok(())
+}
+
+/// Load all entries; a missing history file means no history.
+pub fn load() -> Result<Vec<HistoryEntry>> {
+    let path = history_path();
+
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+
+    let content = fs::read_to_string(&path)?;
+    Ok(parse(&content))
+}
+
+/// Most recent run timestamp per command path.
+pub fn last_used() -> HashMap<String, u64> {
+    let mut map = HashMap::new();
+    for entry in load().unwrap_or_default() {
+        let ts = map.entry(entry.path).or_insert(0);
+        *ts = entry.timestamp.max(*ts);
+    }
+    map
+}
+
+/// Number of recorded runs per command path.
+pub fn use_counts() -> HashMap<String, usize> {
+    let mut map = HashMap::new();
+    for entry in load().unwrap_or_default() {
+        *map.entry(entry.path).or_insert(0) += 1;
+    }
+    map
+}
+
+/// Sidecar holding the last values given to each command's placeholders,
+/// as `{ "path": { "name": "value" } }`.
+pub fn args_path() -> PathBuf {
+    Config::config_dir().join("args.json")
+}
+
+type RememberedArgs = HashMap<String, HashMap<String, String>>;
+
+fn load_args() -> RememberedArgs {
+    fs::read_to_string(args_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_args(all: &RememberedArgs) -> Result<()> {
+    let path = args_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_string_pretty(all).map_err(|e| CmdxError::Config(e.to_string()))?;
+    fs::write(path, json)?;
+    Ok(())
+}
+
+/// Values last used for `path`'s placeholders, by placeholder name.
+pub fn remembered_args(path: &str) -> HashMap<String, String> {
+    load_args().remove(path).unwrap_or_default()
+}
+
+/// Record `values` as the defaults for `path`'s next interactive run.
+pub fn remember_args(path: &str, values: &[(String, String)]) -> Result<()> {
+    let mut all = load_args();
+    let entry = all.entry(path.to_string()).or_default();
+    for (name, value) in values {
+        entry.insert(name.clone(), value.clone());
+    }
+    save_args(&all)
+}
+
+/// Drop `path`'s remembered values; returns whether there were any.
+pub fn forget_args(path: &str) -> Result<bool> {
+    let mut all = load_args();
+    if all.remove(path).is_none() {
+        return Ok(false);
+    }
+    save_args(&all)?;
+    Ok(true)
+}
+
+/// Sidecar holding the path of the command copied most recently.
+pub fn last_copied_path() -> PathBuf {
+    Config::config_dir().join("last_copied")
+}
+
+/// Record `path` as the most recently copied command.
+pub fn remember_copied(path: &str) -> Result<()> {
+    let file = last_copied_path();
+    if let Some(parent) = file.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(file, format!("{}\n", path))?;
+    Ok(())
+}
+
+/// Path of the command copied most recently, if any.
+pub fn last_copied() -> Option<String> {
+    fs::read_to_string(last_copied_path())
+        .ok()
+        .map(|content| content.trim().to_string())
+        .filter(|path| !path.is_empty())
+}
+
+fn parse(content: &str) -> Vec<HistoryEntry> {
+    // Skip malformed lines rather than failing the whole log
+    content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+/// Format a unix timestamp as `YYYY-MM-DD HH:MM:SS` (UTC).
+pub fn format_timestamp(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+
+    // Civil-from-days (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        rem / 3_600,
+        (rem % 3_600) / 60,
+        rem % 60
+    )
+}
+
+/// Parse `YYYY-MM-DD` or `YYYY-MM-DD HH:MM:SS` (UTC) back to a unix
+/// timestamp; the inverse of [`format_timestamp`].
+pub fn parse_timestamp(s: &str) -> Option<u64> {
+    let (date, time) = s.trim().split_once(' ').unwrap_or((s.trim(), "00:00:00"));
+
+    let mut date_parts = date.splitn(3, '-').map(|p| p.parse::<i64>().ok());
+    let (year, month, day) = (date_parts.next()??, date_parts.next()??, date_parts.next()??);
+    let mut time_parts = time.trim().splitn(3, ':').map(|p| p.parse::<u64>().ok());
+    let (hour, minute, second) = (time_parts.next()??, time_parts.next()??, time_parts.next()??);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 || second > 59 {
+        return None;
+    }
+
+    // Days-from-civil (Howard Hinnant's algorithm)
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y.rem_euclid(400);
+    let mp = if month > 2 { month - 3 } else { month + 9 };
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = u64::try_from(era * 146_097 + doe - 719_468).ok()?;
+
+    Some(days * 86_400 + hour * 3_600 + minute * 60 + second)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_timestamp() {
+        for secs in [0, 951_782_400, 1_792_368_000, 1_792_407_845] {
+            assert_eq!(parse_timestamp(&format_timestamp(secs)), Some(secs));
+        }
+        assert_eq!(parse_timestamp("2026-10-17"), Some(1_792_195_200));
+        assert_eq!(parse_timestamp("2026-13-01"), None);
+        assert_eq!(parse_timestamp("tomorrow"), None);
+    }
+
+    #[test]
+    fn test_parse_skips_malformed_lines() {
+        let content = "{\"path\":\"git/status\",\"command\":\"git status\",\"timestamp\":1,\"exit_code\":0}\nnot json\n";
+        let entries = parse(content);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].path, "git/status");
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00:00");
+        assert_eq!(format_timestamp(1_700_000_000), "2023-11-14 22:13:20");
+    }
+}
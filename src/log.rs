@@ -0,0 +1,57 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// How much cmdx prints besides a command's actual output.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub enum Verbosity {
+    /// `-q/--quiet`: no success confirmations, errors only
+    Quiet = 0,
+    Normal = 1,
+    /// `-v/--verbose`: diagnostics too
+    Verbose = 2,
+}
+
+static VERBOSITY: AtomicU8 = AtomicU8::new(Verbosity::Normal as u8);
+
+pub fn set_verbosity(level: Verbosity) {
+    VERBOSITY.store(level as u8, Ordering::Relaxed);
+}
+
+pub fn is_verbose() -> bool {
+    VERBOSITY.load(Ordering::Relaxed) >= Verbosity::Verbose as u8
+}
+
+pub fn is_quiet() -> bool {
+    VERBOSITY.load(Ordering::Relaxed) == Verbosity::Quiet as u8
+}
+
+/// Print a diagnostic line to stderr when `-v/--verbose` is enabled.
+macro_rules! verbose {
+    ($($arg:tt)*) => {
+        if $crate::log::is_verbose() {
+            use colored::Colorize;
+            eprintln!("{} {}", "[cmdx]".dimmed(), format!($($arg)*));
+        }
+    };
+}
+
+/// Print a `✓` confirmation to stdout unless `-q/--quiet` is set.
+macro_rules! success {
+    ($($arg:tt)*) => {
+        if !$crate::log::is_quiet() {
+            use colored::Colorize;
+            println!("{} {}", "✓".green(), format!($($arg)*));
+        }
+    };
+}
+
+/// Like `success!`, but on stderr for commands whose stdout is data.
+macro_rules! esuccess {
+    ($($arg:tt)*) => {
+        if !$crate::log::is_quiet() {
+            use colored::Colorize;
+            eprintln!("{} {}", "✓".green(), format!($($arg)*));
+        }
+    };
+}
+
+pub(crate) use {esuccess, success, verbose};
@@ -3,42 +3,178 @@ mod command;
 mod commands;
 mod config;
 mod error;
+mod formats;
+mod history;
+mod log;
+mod pager;
+mod repl;
 mod store;
+mod text;
+mod trash;
 mod tui;
 
 use clap::Parser;
 use cli::{Cli, Commands};
 use colored::Colorize;
+use std::io::{self, IsTerminal};
 use std::process::ExitCode;
+use std::time::Duration;
 
 fn main() -> ExitCode {
-    // Respect NO_COLOR
-    if std::env::var("NO_COLOR").is_ok() {
-        colored::control::set_override(false);
+    let result = dispatch(Cli::parse());
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{} {}", "error:".red().bold(), e);
+            ExitCode::from(e.exit_code())
+        }
     }
+}
 
-    let cli = Cli::parse();
+/// Apply `--color`; `auto` colors a terminal unless NO_COLOR is set.
+fn set_color(when: &str) {
+    let enabled = match when {
+        "always" => true,
+        "never" => false,
+        _ => std::env::var_os("NO_COLOR").is_none() && io::stdout().is_terminal(),
+    };
+    colored::control::set_override(enabled);
+}
 
-    let result = match cli.command {
+/// Run one parsed command line; shared by `main` and the REPL.
+fn dispatch(cli: Cli) -> error::Result<()> {
+    set_color(&cli.color);
+    log::set_verbosity(match (cli.quiet, cli.verbose) {
+        (true, _) => log::Verbosity::Quiet,
+        (_, true) => log::Verbosity::Verbose,
+        _ => log::Verbosity::Normal,
+    });
+
+    match cli.command {
         Some(Commands::Init) => commands::init(),
-        Some(Commands::Add { path, command, explain, force }) => {
-            commands::add(path, command, explain, force)
+        Some(Commands::Add { batch: Some(file), force, .. }) => commands::add_batch(file, force),
+        Some(Commands::Add {
+            path,
+            command,
+            explain,
+            force,
+            from,
+            no_duplicates,
+            skip_duplicate_check,
+            auto_path,
+            dry_run,
+            auto_explain,
+            replace_newlines,
+            after,
+            expires,
+            batch: None,
+        }) => commands::add(
+            path.unwrap_or_default(),
+            command,
+            explain,
+            commands::AddOptions {
+                force,
+                from,
+                no_duplicates,
+                skip_duplicate_check,
+                auto_path,
+                dry_run,
+                auto_explain,
+                replace_newlines,
+                after,
+                expires,
+            },
+        ),
+        Some(Commands::Show { path, copy, command_only, explain_only, grep }) => {
+            commands::show(path, copy, command_only, explain_only, grep)
+        }
+        Some(Commands::List { path, categories, by_count, json, json_full, no_pager, long, files, plain }) => {
+            commands::list(
+                path,
+                commands::ListOptions { categories, by_count, json, json_full, no_pager, long, files, plain },
+            )
         }
-        Some(Commands::Show { path }) => commands::show(path),
-        Some(Commands::List { path }) => commands::list(path),
-        Some(Commands::Find { query }) => commands::find(query),
-        Some(Commands::Copy { query }) => commands::copy(query),
-        Some(Commands::Run { query, confirm }) => commands::run(query, confirm),
+        Some(Commands::Find { query, run, copy, interactive, no_pager, exec_template, force, field }) => {
+            commands::find(
+                query,
+                commands::FindOptions { run, copy, interactive, no_pager, exec_template, force, field },
+            )
+        }
+        Some(Commands::Copy { query, no_prompt }) => commands::copy(query, no_prompt),
+        Some(Commands::Run {
+            query,
+            confirm,
+            raw,
+            no_prompt,
+            env_file,
+            copy_output,
+            cwd,
+            interactive_args,
+            reset_args,
+            retry,
+            retry_delay,
+        }) => commands::run(
+            query,
+            commands::RunOptions {
+                confirm,
+                raw,
+                no_prompt,
+                env_file,
+                copy_output,
+                cwd,
+                interactive_args,
+                reset_args,
+                retry: commands::Retry { times: retry, delay: Duration::from_secs(retry_delay) },
+            },
+        ),
         Some(Commands::Edit { path }) => commands::edit(path),
-        Some(Commands::Remove { path, force }) => commands::remove(path, force),
-        Some(Commands::Move { src, dst }) => commands::mv(src, dst),
-        Some(Commands::Export { output }) => commands::export(output),
-        Some(Commands::Import { input, force }) => commands::import(input, force),
+        Some(Commands::Annotate { prefix }) => commands::annotate(prefix),
+        Some(Commands::Remove { path, force, recursive }) => {
+            commands::remove(path, force, recursive)
+        }
+        Some(Commands::Move { src, dst, interactive, alias, dry_run }) => {
+            commands::mv(src, dst, interactive, alias, dry_run)
+        }
+        Some(Commands::Export { output, dir, filter, since, include_config, force }) => {
+            commands::export(output, dir, filter, since, include_config, force)
+        }
+        Some(Commands::Import { input, force, include_config, validate_only, from }) => {
+            commands::import(input, force, include_config, validate_only, from)
+        }
         Some(Commands::Completions { shell }) => {
             Cli::generate_completion(shell);
             Ok(())
         }
-        Some(Commands::Pick) => commands::pick(),
+        Some(Commands::Man { subcommand, dir: None }) => Cli::generate_man(subcommand.as_deref()),
+        Some(Commands::Man { dir: Some(dir), .. }) => {
+            Cli::generate_man_pages(std::path::Path::new(&dir))?;
+            log::success!("Man pages written to {}", dir);
+            Ok(())
+        }
+        Some(Commands::Pick { multi, export }) => commands::pick(multi, export),
+        Some(Commands::Templates) => commands::templates(),
+        Some(Commands::History { query }) => commands::history(query),
+        Some(Commands::Migrate) => commands::migrate(),
+        Some(Commands::Lint { fix }) => commands::lint(fix),
+        Some(Commands::Rerun { confirm, raw, env_file, copy_output }) => {
+            let opts = commands::RunOptions { confirm, raw, env_file, copy_output, ..Default::default() };
+            commands::run(commands::LAST_RUN.to_string(), opts)
+        }
+        Some(Commands::Last { print }) => commands::last(print),
+        Some(Commands::Trash) => commands::trash(),
+        Some(Commands::Undo { n, force }) => commands::undo(n, force),
+        Some(Commands::CleanTrash { force }) => commands::clean_trash(force),
+        Some(Commands::Prune { expired: _, dry_run, force }) => commands::prune(dry_run, force),
+        Some(Commands::Replace { from, to, prefix, regex, dry_run, force }) => {
+            commands::replace(from, to, prefix, regex, dry_run, force)
+        }
+        Some(Commands::Stats { unused, older_than, export, format }) => {
+            commands::stats(unused, older_than, export, format)
+        }
+        Some(Commands::Repl) => repl::run(repl::Globals { verbose: cli.verbose, quiet: cli.quiet, color: cli.color }),
+        #[cfg(feature = "serve")]
+        Some(Commands::Serve { host, port }) => commands::serve(host, port),
         None => {
             // Direct path access: cmdx docker/prune
             match cli.path {
@@ -55,23 +191,15 @@ fn main() -> ExitCode {
                 }
             }
         }
-    };
-
-    match result {
-        Ok(()) => ExitCode::SUCCESS,
-        Err(e) => {
-            eprintln!("{} {}", "error:".red().bold(), e);
-            ExitCode::FAILURE
-        }
     }
 }
 
 fn handle_direct_path(path: String) -> error::Result<()> {
     let config = config::Config::load()?;
     
-    match config.core.default_action.as_str() {
-        "run" => commands::run(path, false),
-        "show" => commands::show(path),
-        _ => commands::copy(path), // default to copy
+    match config.action_for(&path) {
+        "run" => commands::run(path, commands::RunOptions::default()),
+        "show" => commands::show(path, false, false, false, None),
+        _ => commands::copy(path, false), // default to copy
     }
 }
@@ -0,0 +1,48 @@
+use crate::log::verbose;
+use std::env;
+use std::io::{self, IsTerminal, Write};
+use std::process::{Command as Process, Stdio};
+
+/// Print `output`, through `$PAGER` (default `less`) when stdout is a
+/// terminal and the text is taller than it. Piped output is never paged.
+pub fn page(output: &str, enabled: bool) {
+    if !enabled || !io::stdout().is_terminal() || fits_screen(output) {
+        print!("{}", output);
+        return;
+    }
+
+    let pager = env::var("PAGER")
+        .ok()
+        .filter(|p| !p.trim().is_empty())
+        .unwrap_or_else(|| "less".to_string());
+    verbose!("paging output through '{}'", pager);
+
+    // Like git: quit if one screen, keep colors, don't clear on exit
+    let mut command = Process::new("sh");
+    command.arg("-c").arg(&pager).stdin(Stdio::piped());
+    if env::var_os("LESS").is_none() {
+        command.env("LESS", "FRX");
+    }
+
+    let mut child = match command.spawn() {
+        Ok(child) => child,
+        Err(_) => {
+            print!("{}", output);
+            return;
+        }
+    };
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // The user may quit the pager early; a broken pipe is fine
+        let _ = stdin.write_all(output.as_bytes());
+    }
+    let _ = child.wait();
+}
+
+fn fits_screen(output: &str) -> bool {
+    match crossterm::terminal::size() {
+        // Leave a row for the shell prompt
+        Ok((_, rows)) => output.lines().count() < rows as usize,
+        Err(_) => true,
+    }
+}
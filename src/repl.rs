@@ -0,0 +1,185 @@
+use crate::cli::{Cli, Commands};
+use crate::config::{self, Config};
+use crate::error::{CmdxError, Result};
+use crate::store;
+use clap::Parser;
+use colored::Colorize;
+use std::io::{self, Write};
+
+/// Global flags given to `cmdx repl` itself.
+pub struct Globals {
+    pub verbose: bool,
+    pub quiet: bool,
+    pub color: String,
+}
+
+/// Read-eval loop over cmdx subcommands until `exit` or end of input.
+///
+/// The config and store are loaded once for the session, and each line
+/// inherits `globals` unless it passes its own.
+pub fn run(globals: Globals) -> Result<()> {
+    open_session()?;
+    let result = read_loop(&globals);
+    config::set_session(None);
+    store::set_session(None);
+    result
+}
+
+/// (Re)load the config and store that the session's commands share.
+fn open_session() -> Result<()> {
+    config::set_session(None);
+    store::set_session(None);
+    let config = Config::load()?;
+    store::set_session(Some(store::open_filesystem(&config)?));
+    config::set_session(Some(config));
+    Ok(())
+}
+
+/// `cli` with the session's `--verbose`/`--quiet`/`--color` where it sets none.
+fn inherit(mut cli: Cli, globals: &Globals) -> Cli {
+    if !cli.verbose && !cli.quiet {
+        cli.verbose = globals.verbose;
+        cli.quiet = globals.quiet;
+    }
+    if cli.color == "auto" {
+        cli.color = globals.color.clone();
+    }
+    cli
+}
+
+fn read_loop(globals: &Globals) -> Result<()> {
+    let stdin = io::stdin();
+
+    loop {
+        print!("{} ", "cmdx>".cyan());
+        io::stdout().flush()?;
+
+        let mut line = String::new();
+        if stdin.read_line(&mut line)? == 0 {
+            // Ctrl+D: finish the prompt line before the shell takes over
+            println!();
+            return Ok(());
+        }
+
+        let line = line.trim();
+        match line {
+            "" => continue,
+            "exit" | "quit" => return Ok(()),
+            _ => {}
+        }
+
+        let args = match split_args(line) {
+            Some(args) => args,
+            None => {
+                eprintln!("{} unterminated quote", "error:".red().bold());
+                continue;
+            }
+        };
+
+        let cli = match Cli::try_parse_from(std::iter::once("cmdx".to_string()).chain(args)) {
+            Ok(cli) => cli,
+            Err(e) => {
+                // Covers --help and --version too, which clap reports as errors
+                let _ = e.print();
+                continue;
+            }
+        };
+
+        if matches!(cli.command, Some(Commands::Repl)) {
+            eprintln!("{} Already in the REPL", "!".yellow());
+            continue;
+        }
+
+        // These can write config.toml or create the store
+        let reload = matches!(
+            cli.command,
+            Some(Commands::Init) | Some(Commands::Import { include_config: true, .. })
+        );
+
+        match crate::dispatch(inherit(cli, globals)) {
+            Ok(()) => {}
+            // Ctrl+C in a run stops that command, not the session
+            Err(CmdxError::Interrupted) => eprintln!(),
+            Err(e) => eprintln!("{} {}", "error:".red().bold(), e),
+        }
+
+        if reload {
+            if let Err(e) = open_session() {
+                eprintln!("{} {}", "error:".red().bold(), e);
+            }
+        }
+    }
+}
+
+/// Split a line into words, honouring single quotes, double quotes and
+/// backslash escapes. Returns `None` for an unterminated quote.
+fn split_args(line: &str) -> Option<Vec<String>> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut quote: Option<char> = None;
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('"'), '\\') | (None, '\\') => {
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+                in_word = true;
+            }
+            (Some(_), c) => current.push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    args.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                current.push(c);
+                in_word = true;
+            }
+        }
+    }
+
+    if quote.is_some() {
+        return None;
+    }
+    if in_word {
+        args.push(current);
+    }
+    Some(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_args() {
+        assert_eq!(
+            split_args(r#"add net/ip "ip -br a" -e 'Brief "addrs"' a\ b ''"#).unwrap(),
+            vec!["add", "net/ip", "ip -br a", "-e", "Brief \"addrs\"", "a b", ""]
+        );
+        assert!(split_args("find 'oops").is_none());
+    }
+
+    #[test]
+    fn test_lines_inherit_session_flags() {
+        let globals = Globals { verbose: false, quiet: true, color: "never".to_string() };
+        let parse = |line: &str| Cli::try_parse_from(line.split_whitespace()).unwrap();
+
+        let cli = inherit(parse("cmdx ls"), &globals);
+        assert!(cli.quiet && !cli.verbose);
+        assert_eq!(cli.color, "never");
+
+        let cli = inherit(parse("cmdx -v --color always ls"), &globals);
+        assert!(cli.verbose && !cli.quiet);
+        assert_eq!(cli.color, "always");
+    }
+}
@@ -1,30 +1,234 @@
 use crate::command::Command;
 use crate::config::Config;
 use crate::error::{CmdxError, Result};
+use std::cell::RefCell;
 use std::fs;
+use std::io;
+use colored::Colorize;
 use std::path::{Path, PathBuf};
+use std::sync::Once;
+use std::time::SystemTime;
+
+/// Current on-disk store format version, recorded in the manifest.
+/// v2 allows compact files that omit an empty explanation line.
+pub const STORE_VERSION: u32 = 2;
+
+/// Manifest file in the store root holding the format version.
+pub const MANIFEST_FILE: &str = ".cmdx-version";
+
+static VERSION_WARNING: Once = Once::new();
+
+/// Redirects followed by `get` before giving up, in case aliases form a loop.
+const MAX_ALIAS_HOPS: usize = 8;
+
+/// Check that a command path is usable as a store-relative file path.
+///
+/// Segments starting with `.` are rejected: they would collide with the
+/// store's own files (`.cmdx-version`, `.git`, `.trash`) and are skipped
+/// when listing anyway. Empty segments (`a//b`, `a/`) are rejected too, since
+/// the file would be stored under a different path than the one given.
+pub fn validate_path(path: &str) -> Result<()> {
+    let invalid = path.is_empty()
+        || path.starts_with('/')
+        || path.contains("..")
+        || path.split('/').any(|segment| segment.is_empty() || segment.starts_with('.'));
+
+    if invalid {
+        return Err(CmdxError::InvalidPath(path.to_string()));
+    }
+    Ok(())
+}
+
+/// The storage operations commands need, independent of where they live.
+///
+/// [`Store`] (a directory of plain-text files) is the only backend today;
+/// `[core] backend` picks one through [`open`]. Filesystem-only concerns such
+/// as the manifest, trash and `$EDITOR` paths stay on `Store` itself.
+pub trait CommandStore {
+    /// Whether the store has been initialized.
+    fn exists(&self) -> bool;
+
+    /// Load the entry at `path` as is, without following an alias.
+    fn get_entry(&self, path: &str) -> Result<Command>;
+
+    fn add(&self, cmd: &Command, overwrite: bool) -> Result<()>;
+
+    fn remove(&self, path: &str) -> Result<()>;
+
+    fn rename(&self, src: &str, dst: &str) -> Result<()>;
+
+    /// Commands under the `prefix` category (all when `None`), sorted by
+    /// path, without aliases.
+    fn list(&self, prefix: Option<&str>) -> Result<Vec<Command>>;
+
+    /// Whether `get` falls back to a case-insensitive match when the exact
+    /// path is missing (`[core] case_insensitive_paths`).
+    fn case_insensitive_paths(&self) -> bool {
+        false
+    }
+
+    /// Load a command, following `mv --alias` redirects to where it moved.
+    ///
+    /// Lookup precedence is exact path, then (if enabled) the one path equal
+    /// ignoring case; callers fall back to fuzzy matching after that.
+    fn get(&self, path: &str) -> Result<Command> {
+        let mut cmd = match self.get_entry(path) {
+            Err(CmdxError::NotFound(_)) if self.case_insensitive_paths() => self.get_ignoring_case(path)?,
+            entry => entry?,
+        };
+
+        for _ in 0..MAX_ALIAS_HOPS {
+            if cmd.alias.is_empty() {
+                return Ok(cmd);
+            }
+            eprintln!(
+                "{} {} has moved to {}; update scripts to use the new path",
+                "!".yellow(),
+                cmd.path,
+                cmd.alias.cyan()
+            );
+            cmd = self.get_entry(&cmd.alias)?;
+        }
 
+        Err(CmdxError::NotFound(path.to_string()))
+    }
+
+    /// The command whose path equals `path` ignoring case. Several such
+    /// commands count as no match, since picking one would be a guess.
+    fn get_ignoring_case(&self, path: &str) -> Result<Command> {
+        let wanted = path.to_lowercase();
+        let mut matches = self.list(None)?.into_iter().filter(|c| c.path.to_lowercase() == wanted);
+        match (matches.next(), matches.next()) {
+            (Some(cmd), None) => Ok(cmd),
+            _ => Err(CmdxError::NotFound(path.to_string())),
+        }
+    }
+
+    /// Like `list`, but a prefix that is not a category also matches partial
+    /// names: `git/sta` finds `git/stash/*` and `git/status`. A trailing `/`
+    /// asks for the category only.
+    fn list_matching(&self, prefix: Option<&str>) -> Result<Vec<Command>> {
+        let Some(prefix) = prefix else {
+            return self.list(None);
+        };
+
+        let trimmed = prefix.trim_end_matches('/');
+        let category = self.list(Some(trimmed))?;
+        if prefix.ends_with('/') || !category.is_empty() {
+            return Ok(category);
+        }
+
+        let mut commands = self.list(None)?;
+        commands.retain(|c| c.path.starts_with(trimmed));
+        Ok(commands)
+    }
+}
+
+/// The backend selected by `[core] backend`.
+pub fn open(config: &Config) -> Result<Box<dyn CommandStore>> {
+    Ok(Box::new(open_filesystem(config)?))
+}
+
+thread_local! {
+    /// Opened once for a `cmdx repl` session, see [`set_session`].
+    static SESSION: RefCell<Option<Store>> = const { RefCell::new(None) };
+}
+
+/// Make [`open`] and [`open_filesystem`] return `store` for the rest of a
+/// `cmdx repl` session (or open from the config again for `None`).
+pub fn set_session(store: Option<Store>) {
+    SESSION.with(|session| *session.borrow_mut() = store);
+}
+
+/// The filesystem store, for commands that need its file-level methods
+/// (`command_path`, `modified`, ...). Rejects `[core] backend` like [`open`].
+pub fn open_filesystem(config: &Config) -> Result<Store> {
+    if let Some(store) = SESSION.with(|session| session.borrow().clone()) {
+        return Ok(store);
+    }
+
+    match config.core.backend.as_str() {
+        "filesystem" => Store::new(config),
+        other => Err(CmdxError::Config(format!(
+            "Unknown store backend '{}' (expected: filesystem)",
+            other
+        ))),
+    }
+}
+
+#[derive(Clone)]
 pub struct Store {
     root: PathBuf,
+    prune_empty_dirs: bool,
+    compact_files: bool,
+    case_insensitive_paths: bool,
 }
 
 impl Store {
-    pub fn new(config: &Config) -> Self {
-        Self {
-            root: config.store_path(),
-        }
+    pub fn new(config: &Config) -> Result<Self> {
+        Ok(Self {
+            root: config.store_path()?,
+            prune_empty_dirs: config.core.prune_empty_dirs,
+            compact_files: config.core.compact_files,
+            case_insensitive_paths: config.core.case_insensitive_paths,
+        })
     }
 
     pub fn root(&self) -> &Path {
         &self.root
     }
 
-    pub fn exists(&self) -> bool {
-        self.root.exists()
+    /// Verify the store root is a readable directory.
+    pub fn validate_root(&self) -> Result<()> {
+        if !self.root.exists() {
+            return Err(CmdxError::NotInitialized);
+        }
+
+        if !self.root.is_dir() {
+            return Err(CmdxError::StoreNotDirectory(self.root.clone()));
+        }
+
+        fs::read_dir(&self.root).map_err(|e| CmdxError::StoreUnreadable(self.root.clone(), e))?;
+
+        VERSION_WARNING.call_once(|| {
+            if let Ok(Some(version)) = self.version() {
+                if version > STORE_VERSION {
+                    eprintln!(
+                        "{} Store format v{} is newer than this cmdx supports (v{}). Upgrade cmdx.",
+                        "!".yellow(),
+                        version,
+                        STORE_VERSION
+                    );
+                }
+            }
+        });
+
+        Ok(())
+    }
+
+    /// Format version from the manifest; `None` for stores predating it.
+    pub fn version(&self) -> Result<Option<u32>> {
+        let manifest = self.root.join(MANIFEST_FILE);
+
+        if !manifest.exists() {
+            return Ok(None);
+        }
+
+        fs::read_to_string(&manifest)?
+            .trim()
+            .parse()
+            .map(Some)
+            .map_err(|_| CmdxError::InvalidFormat(manifest))
+    }
+
+    pub fn write_version(&self, version: u32) -> Result<()> {
+        fs::write(self.root.join(MANIFEST_FILE), format!("{}\n", version))?;
+        Ok(())
     }
 
     pub fn init(&self) -> Result<()> {
         fs::create_dir_all(&self.root)?;
+        self.write_version(STORE_VERSION)?;
         Ok(())
     }
 
@@ -32,7 +236,114 @@ impl Store {
         self.root.join(path)
     }
 
-    pub fn get(&self, path: &str) -> Result<Command> {
+    /// What `add` writes for `cmd` in this store's format.
+    pub fn file_content(&self, cmd: &Command) -> Result<String> {
+        // Older stores may be read by a cmdx that expects two lines
+        let compact = self.compact_files && self.version()?.unwrap_or(1) >= 2;
+        Ok(cmd.to_file_content_with(compact))
+    }
+
+    /// Last modification time of a command file.
+    pub fn modified(&self, path: &str) -> Result<SystemTime> {
+        Ok(fs::metadata(self.command_path(path))?.modified()?)
+    }
+
+    #[allow(dead_code)]
+    pub fn all_paths(&self) -> Result<Vec<String>> {
+        let commands = self.list(None)?;
+        Ok(commands.into_iter().map(|c| c.path).collect())
+    }
+
+    /// Depth-first walk in name order, so the result does not depend on
+    /// the filesystem's `read_dir` order.
+    fn collect_commands(&self, dir: &Path, commands: &mut Vec<Command>) -> Result<()> {
+        if !dir.is_dir() {
+            if dir.is_file() {
+                let path = self.relative_path(dir)?;
+                if let Ok(cmd) = Command::from_file(&path, dir) {
+                    commands.push(cmd);
+                }
+            }
+            return Ok(());
+        }
+
+        let mut entries = fs::read_dir(dir)?.collect::<io::Result<Vec<_>>>()?;
+        entries.sort_by_key(|e| e.file_name());
+
+        for entry in entries {
+            let path = entry.path();
+
+            // Dotfiles (manifest, editor swap files, .git) are not commands
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+
+            if path.is_dir() {
+                self.collect_commands(&path, commands)?;
+            } else if path.is_file() {
+                let rel_path = self.relative_path(&path)?;
+                // Aliases only redirect lookups; listing them would duplicate commands
+                match Command::from_file(&rel_path, &path) {
+                    Ok(cmd) if cmd.alias.is_empty() => commands.push(cmd),
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn relative_path(&self, path: &Path) -> Result<String> {
+        path.strip_prefix(&self.root)
+            .map(|p| p.to_string_lossy().to_string())
+            .map_err(|_| CmdxError::InvalidPath(path.display().to_string()))
+    }
+
+    fn cleanup_empty_dirs(&self, path: &Path) -> Result<()> {
+        // Some users keep empty categories as placeholders
+        if !self.prune_empty_dirs {
+            return Ok(());
+        }
+
+        let mut current = path.parent();
+
+        while let Some(dir) = current {
+            if dir == self.root {
+                break;
+            }
+
+            if dir.exists() && dir.is_dir() {
+                if fs::read_dir(dir)?.next().is_none() {
+                    fs::remove_dir(dir)?;
+                } else {
+                    break;
+                }
+            }
+
+            current = dir.parent();
+        }
+
+        Ok(())
+    }
+}
+
+impl CommandStore for Store {
+    fn exists(&self) -> bool {
+        self.root.exists()
+    }
+
+    fn case_insensitive_paths(&self) -> bool {
+        self.case_insensitive_paths
+    }
+
+    fn get_entry(&self, path: &str) -> Result<Command> {
+        self.validate_root()?;
+
+        // `root.join` would happily leave the store for `/etc/passwd` or `../x`;
+        // such a path can't name a command, so it is simply not found
+        if validate_path(path).is_err() {
+            return Err(CmdxError::NotFound(path.to_string()));
+        }
         let file_path = self.command_path(path);
 
         if !file_path.exists() {
@@ -42,7 +353,9 @@ impl Store {
         Command::from_file(path, &file_path)
     }
 
-    pub fn add(&self, cmd: &Command, overwrite: bool) -> Result<()> {
+    fn add(&self, cmd: &Command, overwrite: bool) -> Result<()> {
+        self.validate_root()?;
+        validate_path(&cmd.path)?;
         let file_path = self.command_path(&cmd.path);
 
         if file_path.exists() && !overwrite {
@@ -53,11 +366,12 @@ impl Store {
             fs::create_dir_all(parent)?;
         }
 
-        fs::write(&file_path, cmd.to_file_content())?;
+        fs::write(&file_path, self.file_content(cmd)?)?;
         Ok(())
     }
 
-    pub fn remove(&self, path: &str) -> Result<()> {
+    fn remove(&self, path: &str) -> Result<()> {
+        validate_path(path)?;
         let file_path = self.command_path(path);
 
         if !file_path.exists() {
@@ -69,7 +383,10 @@ impl Store {
         Ok(())
     }
 
-    pub fn rename(&self, src: &str, dst: &str) -> Result<()> {
+    fn rename(&self, src: &str, dst: &str) -> Result<()> {
+        self.validate_root()?;
+        validate_path(src)?;
+        validate_path(dst)?;
         let src_path = self.command_path(src);
         let dst_path = self.command_path(dst);
 
@@ -90,10 +407,8 @@ impl Store {
         Ok(())
     }
 
-    pub fn list(&self, prefix: Option<&str>) -> Result<Vec<Command>> {
-        if !self.exists() {
-            return Err(CmdxError::NotInitialized);
-        }
+    fn list(&self, prefix: Option<&str>) -> Result<Vec<Command>> {
+        self.validate_root()?;
 
         let search_root = match prefix {
             Some(p) => self.command_path(p),
@@ -110,65 +425,269 @@ impl Store {
         Ok(commands)
     }
 
-    #[allow(dead_code)]
-    pub fn all_paths(&self) -> Result<Vec<String>> {
-        let commands = self.list(None)?;
-        Ok(commands.into_iter().map(|c| c.path).collect())
-    }
+    // Directories make the category check a stat instead of a full listing
+    fn list_matching(&self, prefix: Option<&str>) -> Result<Vec<Command>> {
+        let Some(prefix) = prefix else {
+            return self.list(None);
+        };
 
-    fn collect_commands(&self, dir: &Path, commands: &mut Vec<Command>) -> Result<()> {
-        if !dir.is_dir() {
-            if dir.is_file() {
-                let path = self.relative_path(dir)?;
-                if let Ok(cmd) = Command::from_file(&path, dir) {
-                    commands.push(cmd);
-                }
-            }
-            return Ok(());
+        // Directory prefixes are the fast path
+        let trimmed = prefix.trim_end_matches('/');
+        if prefix.ends_with('/') || self.command_path(trimmed).is_dir() {
+            return self.list(Some(trimmed));
         }
 
-        for entry in fs::read_dir(dir)? {
-            let entry = entry?;
-            let path = entry.path();
+        // Only the parent directory can hold partial matches
+        let parent = trimmed.rsplit_once('/').map(|(dir, _)| dir);
+        let mut commands = self.list(parent)?;
+        commands.retain(|c| c.path.starts_with(trimmed));
+        Ok(commands)
+    }
+}
 
-            if path.is_dir() {
-                self.collect_commands(&path, commands)?;
-            } else if path.is_file() {
-                let rel_path = self.relative_path(&path)?;
-                if let Ok(cmd) = Command::from_file(&rel_path, &path) {
-                    commands.push(cmd);
-                }
-            }
+/// A [`CommandStore`] held in memory, so command modules can be tested
+/// without a temporary store directory.
+#[cfg(test)]
+#[derive(Default)]
+pub struct MemoryStore {
+    commands: std::cell::RefCell<std::collections::BTreeMap<String, Command>>,
+}
+
+#[cfg(test)]
+impl CommandStore for MemoryStore {
+    fn exists(&self) -> bool {
+        true
+    }
+
+    fn get_entry(&self, path: &str) -> Result<Command> {
+        self.commands
+            .borrow()
+            .get(path)
+            .cloned()
+            .ok_or_else(|| CmdxError::NotFound(path.to_string()))
+    }
+
+    fn add(&self, cmd: &Command, overwrite: bool) -> Result<()> {
+        validate_path(&cmd.path)?;
+        let mut commands = self.commands.borrow_mut();
+        if commands.contains_key(&cmd.path) && !overwrite {
+            return Err(CmdxError::AlreadyExists(PathBuf::from(&cmd.path)));
         }
+        commands.insert(cmd.path.clone(), cmd.clone());
+        Ok(())
+    }
+
+    fn remove(&self, path: &str) -> Result<()> {
+        self.commands
+            .borrow_mut()
+            .remove(path)
+            .map(|_| ())
+            .ok_or_else(|| CmdxError::NotFound(path.to_string()))
+    }
 
+    fn rename(&self, src: &str, dst: &str) -> Result<()> {
+        validate_path(dst)?;
+        let mut commands = self.commands.borrow_mut();
+        if commands.contains_key(dst) {
+            return Err(CmdxError::AlreadyExists(PathBuf::from(dst)));
+        }
+        let mut cmd = commands.remove(src).ok_or_else(|| CmdxError::NotFound(src.to_string()))?;
+        cmd.path = dst.to_string();
+        commands.insert(dst.to_string(), cmd);
         Ok(())
     }
 
-    fn relative_path(&self, path: &Path) -> Result<String> {
-        path.strip_prefix(&self.root)
-            .map(|p| p.to_string_lossy().to_string())
-            .map_err(|_| CmdxError::InvalidPath(path.display().to_string()))
+    fn list(&self, prefix: Option<&str>) -> Result<Vec<Command>> {
+        let category = prefix.map(|p| format!("{}/", p));
+        Ok(self
+            .commands
+            .borrow()
+            .values()
+            .filter(|c| c.alias.is_empty())
+            .filter(|c| match (prefix, &category) {
+                (Some(p), Some(dir)) => c.path == p || c.path.starts_with(dir.as_str()),
+                _ => true,
+            })
+            .cloned()
+            .collect())
     }
+}
 
-    fn cleanup_empty_dirs(&self, path: &Path) -> Result<()> {
-        let mut current = path.parent();
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        while let Some(dir) = current {
-            if dir == self.root {
-                break;
-            }
+    fn store_at(root: PathBuf) -> Store {
+        let mut config = Config::default();
+        config.core.store_path = root.to_string_lossy().to_string();
+        Store::new(&config).unwrap()
+    }
 
-            if dir.exists() && dir.is_dir() {
-                if fs::read_dir(dir)?.next().is_none() {
-                    fs::remove_dir(dir)?;
-                } else {
-                    break;
-                }
-            }
+    #[test]
+    fn test_validate_path_rejects_reserved() {
+        assert!(validate_path("docker/prune").is_ok());
+        assert!(validate_path("tools/v1.2").is_ok());
+        assert!(validate_path(".trash/foo").is_err());
+        assert!(validate_path(".git/config").is_err());
+        assert!(validate_path(MANIFEST_FILE).is_err());
+        assert!(validate_path("a/.hidden").is_err());
+        assert!(validate_path("../escape").is_err());
+        assert!(validate_path("/abs").is_err());
+        assert!(validate_path("a//b").is_err());
+        assert!(validate_path("a/").is_err());
+        assert!(validate_path("a/b/").is_err());
+    }
 
-            current = dir.parent();
+    #[test]
+    fn test_collect_commands_order_is_stable() {
+        let root = std::env::temp_dir().join(format!("cmdx-store-order-{}", std::process::id()));
+        let store = store_at(root.clone());
+        store.init().unwrap();
+
+        // Created out of order so creation order can't explain the result
+        let mut paths: Vec<String> = (0..40).map(|i| format!("cat{}/cmd{}", i % 7, (i * 13) % 40)).collect();
+        paths.push("cat3-x".to_string());
+        for path in paths.iter().rev() {
+            store.add(&Command::new(path, "true", ""), true).unwrap();
         }
 
-        Ok(())
+        let collect = || {
+            let mut commands = Vec::new();
+            store.collect_commands(&root, &mut commands).unwrap();
+            commands.into_iter().map(|c| c.path).collect::<Vec<_>>()
+        };
+        let first = collect();
+        assert_eq!(first.len(), 41);
+        assert_eq!(first, collect());
+
+        // Each directory is walked in name order: `cat3/` comes before `cat3-x`
+        let cat3 = first.iter().position(|p| p.starts_with("cat3/")).unwrap();
+        let cat3_x = first.iter().position(|p| p == "cat3-x").unwrap();
+        assert!(cat3 < cat3_x);
+        let cat0: Vec<&String> = first.iter().filter(|p| p.starts_with("cat0/")).collect();
+        assert!(cat0.windows(2).all(|w| w[0] < w[1]));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_manifest_written_and_skipped() {
+        let root = std::env::temp_dir().join(format!("cmdx-store-manifest-{}", std::process::id()));
+        let store = store_at(root.clone());
+
+        store.init().unwrap();
+        assert_eq!(store.version().unwrap(), Some(STORE_VERSION));
+        assert!(store.list(None).unwrap().is_empty());
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_prune_empty_dirs_option() {
+        for prune in [true, false] {
+            let root = std::env::temp_dir().join(format!("cmdx-store-prune-{}-{}", prune, std::process::id()));
+            let mut config = Config::default();
+            config.core.store_path = root.to_string_lossy().to_string();
+            config.core.prune_empty_dirs = prune;
+            let store = Store::new(&config).unwrap();
+
+            store.init().unwrap();
+            store.add(&Command::new("cat/sub/cmd", "true", ""), false).unwrap();
+            store.remove("cat/sub/cmd").unwrap();
+
+            assert_eq!(root.join("cat/sub").exists(), !prune);
+            assert_eq!(root.join("cat").exists(), !prune);
+            fs::remove_dir_all(&root).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_list_matching_partial_names() {
+        let root = std::env::temp_dir().join(format!("cmdx-store-partial-{}", std::process::id()));
+        let store = store_at(root.clone());
+        store.init().unwrap();
+        for path in ["git/stash/pop", "git/status", "git/log", "gitk"] {
+            store.add(&Command::new(path, "true", ""), false).unwrap();
+        }
+
+        let paths = |prefix| -> Vec<String> {
+            store.list_matching(Some(prefix)).unwrap().into_iter().map(|c| c.path).collect()
+        };
+        assert_eq!(paths("git/sta"), vec!["git/stash/pop", "git/status"]);
+        assert_eq!(paths("git"), vec!["git/log", "git/stash/pop", "git/status"]);
+        assert!(paths("gi/").is_empty());
+        assert_eq!(paths("gi"), vec!["git/log", "git/stash/pop", "git/status", "gitk"]);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_memory_store_follows_aliases_and_matches_partially() {
+        let store = MemoryStore::default();
+        for path in ["git/stash/pop", "git/status", "git/log", "gitk"] {
+            store.add(&Command::new(path, "true", ""), false).unwrap();
+        }
+        store.rename("git/log", "git/history").unwrap();
+        store.add(&Command::alias_to("git/log", "git/history"), false).unwrap();
+
+        assert_eq!(store.get("git/log").unwrap().path, "git/history");
+        assert!(matches!(store.get_entry("git/nope"), Err(CmdxError::NotFound(_))));
+
+        let paths = |prefix| -> Vec<String> {
+            store.list_matching(Some(prefix)).unwrap().into_iter().map(|c| c.path).collect()
+        };
+        assert_eq!(paths("git/sta"), vec!["git/stash/pop", "git/status"]);
+        assert_eq!(paths("git"), vec!["git/history", "git/stash/pop", "git/status"]);
+        assert!(paths("gi/").is_empty());
+    }
+
+    #[test]
+    fn test_get_falls_back_to_case_insensitive_path() {
+        let root = std::env::temp_dir().join(format!("cmdx-store-case-{}", std::process::id()));
+        let mut config = Config::default();
+        config.core.store_path = root.to_string_lossy().to_string();
+        config.core.case_insensitive_paths = true;
+        let store = Store::new(&config).unwrap();
+        store.init().unwrap();
+        for path in ["docker/prune", "git/Log", "git/log-all"] {
+            store.add(&Command::new(path, "true", ""), true).unwrap();
+        }
+
+        assert_eq!(store.get("docker/prune").unwrap().path, "docker/prune");
+        assert_eq!(store.get("Docker/Prune").unwrap().path, "docker/prune");
+        assert_eq!(store.get("GIT/LOG").unwrap().path, "git/Log");
+        assert!(matches!(store.get("docker/prun"), Err(CmdxError::NotFound(_))));
+        assert!(matches!(store_at(root.clone()).get("Docker/Prune"), Err(CmdxError::NotFound(_))));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_store_root_is_file() {
+        let root = std::env::temp_dir().join(format!("cmdx-store-file-{}", std::process::id()));
+        fs::write(&root, "not a directory").unwrap();
+
+        let store = store_at(root.clone());
+        let err = store.list(None).unwrap_err();
+
+        assert!(matches!(err, CmdxError::StoreNotDirectory(_)));
+        assert!(err.to_string().contains("not a directory"));
+        fs::remove_file(&root).unwrap();
+    }
+
+    #[test]
+    fn test_paths_outside_store_are_not_read() {
+        let base = std::env::temp_dir().join(format!("cmdx-store-escape-{}", std::process::id()));
+        let root = base.join("store");
+        let store = store_at(root.clone());
+        store.init().unwrap();
+        fs::write(base.join("secret"), "cat secret\n\n").unwrap();
+
+        assert!(matches!(store.get_entry("../secret"), Err(CmdxError::NotFound(_))));
+        let absolute = base.join("secret").to_string_lossy().into_owned();
+        assert!(matches!(store.get_entry(&absolute), Err(CmdxError::NotFound(_))));
+        assert!(store.remove("../secret").is_err());
+        assert!(base.join("secret").exists());
+
+        fs::remove_dir_all(&base).unwrap();
     }
 }
@@ -0,0 +1,123 @@
+use crate::config::DisplayConfig;
+use colored::Colorize;
+use std::io::{self, IsTerminal};
+use std::mem;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Width to wrap CLI output at, or `None` when wrapping is off or stdout is
+/// not a terminal.
+pub fn output_width(display: &DisplayConfig) -> Option<usize> {
+    if !display.wrap {
+        return None;
+    }
+
+    terminal_width().map(|w| display.wrap_width.unwrap_or(w))
+}
+
+/// Width of the terminal stdout is attached to, or `None` when piped.
+pub fn terminal_width() -> Option<usize> {
+    if !io::stdout().is_terminal() {
+        return None;
+    }
+
+    std::env::var("COLUMNS")
+        .ok()
+        .and_then(|c| c.parse().ok())
+        .or_else(|| crossterm::terminal::size().ok().map(|(w, _)| w as usize))
+}
+
+/// Print `→ explanation` starting at column `indent`, wrapping continuation
+/// lines under the first word.
+pub fn print_explanation(explanation: &str, indent: usize, width: Option<usize>) {
+    print!("{}", format_explanation(explanation, indent, width));
+}
+
+/// Same as [`print_explanation`], but returned as newline-terminated text.
+pub fn format_explanation(explanation: &str, indent: usize, width: Option<usize>) -> String {
+    let pad = " ".repeat(indent);
+    let lines = match width {
+        Some(w) => wrap_text(explanation, w.saturating_sub(indent + 2).max(10)),
+        None => vec![explanation.to_string()],
+    };
+
+    let mut out = String::new();
+    for (i, line) in lines.iter().enumerate() {
+        if i == 0 {
+            out.push_str(&format!("{}{} {}\n", pad, "→".dimmed(), line.dimmed()));
+        } else {
+            out.push_str(&format!("{}  {}\n", pad, line.dimmed()));
+        }
+    }
+    out
+}
+
+/// Wrap `text` to lines of at most `max_width` display columns. Words wider
+/// than a line are split between characters.
+pub fn wrap_text(text: &str, max_width: usize) -> Vec<String> {
+    if max_width == 0 {
+        return vec![text.to_string()];
+    }
+
+    let mut lines = Vec::new();
+    let mut current_line = String::new();
+    let mut current_width = 0;
+
+    for word in text.split_whitespace() {
+        let width = word.width();
+        if !current_line.is_empty() && current_width + 1 + width <= max_width {
+            current_line.push(' ');
+            current_line.push_str(word);
+            current_width += 1 + width;
+            continue;
+        }
+
+        if !current_line.is_empty() {
+            lines.push(mem::take(&mut current_line));
+        }
+        current_width = 0;
+        for c in word.chars() {
+            let char_width = c.width().unwrap_or(0);
+            if current_width + char_width > max_width && !current_line.is_empty() {
+                lines.push(mem::take(&mut current_line));
+                current_width = 0;
+            }
+            current_line.push(c);
+            current_width += char_width;
+        }
+    }
+
+    if !current_line.is_empty() {
+        lines.push(current_line);
+    }
+
+    if lines.is_empty() {
+        lines.push(String::new());
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_text() {
+        assert_eq!(wrap_text("one two three", 7), vec!["one two", "three"]);
+        assert_eq!(wrap_text("abcdefghij", 4), vec!["abcd", "efgh", "ij"]);
+        assert_eq!(wrap_text("", 10), vec![""]);
+        assert_eq!(wrap_text("ab cdefgh", 4), vec!["ab", "cdef", "gh"]);
+    }
+
+    #[test]
+    fn test_wrap_text_non_ascii() {
+        // Each 中 is 3 bytes and 2 columns wide
+        let long = format!("a{}", "中".repeat(200));
+        let lines = wrap_text(&long, 75);
+        assert!(lines.iter().all(|l| l.width() <= 75));
+        assert_eq!(lines.concat(), long);
+
+        assert_eq!(wrap_text("café crème", 10), vec!["café crème"]);
+        assert_eq!(wrap_text("中中中 x", 6), vec!["中中中", "x"]);
+    }
+}
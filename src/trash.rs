@@ -0,0 +1,123 @@
+use crate::command::Command;
+use crate::config::Config;
+use crate::error::{CmdxError, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A removed command, kept as one JSON file so the original path survives.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashEntry {
+    pub deleted_at: u64,
+    pub command: Command,
+    #[serde(skip)]
+    file: PathBuf,
+}
+
+pub struct Trash {
+    dir: PathBuf,
+}
+
+impl Trash {
+    pub fn new() -> Self {
+        Self {
+            dir: Config::config_dir().join("trash"),
+        }
+    }
+
+    /// Move a command's content into the trash before it is removed.
+    pub fn put(&self, cmd: &Command) -> Result<()> {
+        fs::create_dir_all(&self.dir)?;
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        let entry = TrashEntry {
+            deleted_at: now.as_secs(),
+            command: cmd.clone(),
+            file: PathBuf::new(),
+        };
+        let json = serde_json::to_string_pretty(&entry)
+            .map_err(|e| CmdxError::Config(e.to_string()))?;
+
+        // Nanosecond names sort chronologically and don't collide in a bulk rm
+        let mut nanos = now.as_nanos();
+        let mut file = self.dir.join(format!("{}.json", nanos));
+        while file.exists() {
+            nanos += 1;
+            file = self.dir.join(format!("{}.json", nanos));
+        }
+
+        fs::write(file, json)?;
+        Ok(())
+    }
+
+    /// Trashed commands, most recently deleted first.
+    pub fn list(&self) -> Result<Vec<TrashEntry>> {
+        if !self.dir.exists() {
+            return Ok(vec![]);
+        }
+
+        let mut files: Vec<PathBuf> = fs::read_dir(&self.dir)?
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        files.sort();
+        files.reverse();
+
+        Ok(files
+            .into_iter()
+            .filter_map(|file| {
+                let content = fs::read_to_string(&file).ok()?;
+                let mut entry: TrashEntry = serde_json::from_str(&content).ok()?;
+                entry.file = file;
+                Some(entry)
+            })
+            .collect())
+    }
+
+    /// Take entry `n` (1 = most recent) out of the trash.
+    pub fn take(&self, n: usize) -> Result<TrashEntry> {
+        let entry = self
+            .list()?
+            .into_iter()
+            .nth(n.saturating_sub(1))
+            .ok_or_else(|| CmdxError::NotFound(format!("trash entry {}", n)))?;
+
+        fs::remove_file(&entry.file)?;
+        Ok(entry)
+    }
+
+    /// Permanently delete everything in the trash, returning how many.
+    pub fn clear(&self) -> Result<usize> {
+        let entries = self.list()?;
+        for entry in &entries {
+            fs::remove_file(&entry.file)?;
+        }
+        Ok(entries.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_put_list_take() {
+        let dir = std::env::temp_dir().join(format!("cmdx-trash-{}", std::process::id()));
+        let trash = Trash { dir: dir.clone() };
+
+        trash.put(&Command::new("a/one", "echo 1", "")).unwrap();
+        trash.put(&Command::new("a/two", "echo 2", "")).unwrap();
+
+        let entries = trash.list().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].command.path, "a/two");
+
+        let taken = trash.take(2).unwrap();
+        assert_eq!(taken.command.path, "a/one");
+        assert_eq!(trash.clear().unwrap(), 1);
+        assert!(trash.list().unwrap().is_empty());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,16 +1,25 @@
 use crate::command::Command;
+use crate::commands::{copy_to_clipboard, notes_score, NOTES_MIN_QUERY};
 use crate::config::Config;
 use crate::error::{CmdxError, Result};
-use crate::store::Store;
+use crate::history::{self, format_timestamp, now};
+use crate::store::{self, CommandStore};
+use crate::trash::Trash;
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event},
+    cursor::Show,
+    event::{
+        self, DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste,
+        EnableMouseCapture, Event,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use fuzzy_matcher::skim::SkimMatcherV2;
 use fuzzy_matcher::FuzzyMatcher;
 use ratatui::{backend::CrosstermBackend, Terminal};
+use std::collections::{BTreeSet, HashMap};
 use std::io;
+use std::panic;
 
 use super::event::handle_key_event;
 use super::ui::draw_ui;
@@ -22,8 +31,38 @@ pub enum Mode {
     Edit,
     Delete,
     Help,
+    Palette,
 }
 
+/// What the caller should do with the command picked in the TUI.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PickAction {
+    Copy,
+    Run,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PaletteAction {
+    Add,
+    Edit,
+    Delete,
+    CopyPath,
+    Run,
+    Help,
+    ToggleScores,
+}
+
+/// Command palette entries: action, name to filter on, equivalent key.
+pub const PALETTE_ACTIONS: [(PaletteAction, &str, &str); 7] = [
+    (PaletteAction::Add, "add", "F2"),
+    (PaletteAction::Edit, "edit", "F3"),
+    (PaletteAction::Delete, "delete", "F4"),
+    (PaletteAction::CopyPath, "copy-path", ""),
+    (PaletteAction::Run, "run", ""),
+    (PaletteAction::Help, "help", "F1"),
+    (PaletteAction::ToggleScores, "toggle-scores", "F5"),
+];
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum InputField {
     Path,
@@ -31,6 +70,50 @@ pub enum InputField {
     Description,
 }
 
+/// Feedback shown in the status line, styled by level.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Message {
+    Info(String),
+    Success(String),
+    Warning(String),
+    Error(String),
+}
+
+impl Message {
+    pub fn text(&self) -> &str {
+        match self {
+            Message::Info(text) | Message::Success(text) | Message::Warning(text) | Message::Error(text) => text,
+        }
+    }
+}
+
+/// A message is cleared after this many redraws, i.e. about as many keys.
+const MESSAGE_DRAWS: usize = 5;
+
+/// Store changes made during one TUI session, summarized after it exits.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ChangeLog {
+    pub added: Vec<String>,
+    /// `path`, or `old → new` when the edit also renamed it.
+    pub edited: Vec<String>,
+    pub deleted: Vec<String>,
+}
+
+impl ChangeLog {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.edited.is_empty() && self.deleted.is_empty()
+    }
+}
+
+/// How a TUI session ended: the picked command, if any, and what changed.
+/// In multi-select mode `marked` holds every command marked when the
+/// session was confirmed, in store order.
+pub struct Session {
+    pub picked: Option<(Command, PickAction)>,
+    pub marked: Vec<Command>,
+    pub changes: ChangeLog,
+}
+
 pub struct App {
     pub input: String,
     pub cursor_position: usize,
@@ -41,13 +124,27 @@ pub struct App {
     pub visible_height: usize,
     pub should_quit: bool,
     pub selected_command: Option<Command>,
+    pub pick_action: PickAction,
     pub mode: Mode,
     pub form_path: String,
     pub form_command: String,
     pub form_description: String,
     pub active_field: InputField,
-    pub message: Option<(String, bool)>, // (message, is_error)
+    pub message: Option<Message>,
+    message_draws: usize, // redraws left before `message` is cleared
     pub editing_original_path: Option<String>,
+    pub icons: String, // nerd | emoji | none
+    pub list_format: String,
+    pub author: String, // recorded on commands added in the TUI
+    pub clipboard_tool: String,
+    pub palette_input: String,
+    pub palette_selected: usize,
+    pub show_scores: bool, // match scores and highlighted characters in the list
+    filter_query: String, // query `filtered` was computed for
+    pub use_counts: HashMap<String, usize>,
+    pub changes: ChangeLog,
+    pub multi: bool, // Tab marks commands instead of moving
+    pub marked: BTreeSet<String>, // paths marked in multi-select mode
     matcher: SkimMatcherV2,
 }
 
@@ -66,13 +163,27 @@ impl App {
             visible_height: 10,
             should_quit: false,
             selected_command: None,
+            pick_action: PickAction::Copy,
             mode: Mode::Normal,
             form_path: String::new(),
             form_command: String::new(),
             form_description: String::new(),
             active_field: InputField::Path,
             message: None,
+            message_draws: 0,
             editing_original_path: None,
+            icons: "nerd".to_string(),
+            list_format: "{icon} {path}".to_string(),
+            author: String::new(),
+            clipboard_tool: "auto".to_string(),
+            palette_input: String::new(),
+            palette_selected: 0,
+            show_scores: false,
+            filter_query: String::new(),
+            use_counts: HashMap::new(),
+            changes: ChangeLog::default(),
+            multi: false,
+            marked: BTreeSet::new(),
             matcher: SkimMatcherV2::default(),
         }
     }
@@ -81,28 +192,32 @@ impl App {
         if self.input.is_empty() {
             self.filtered = (0..self.commands.len()).map(|i| (i, 0)).collect();
         } else {
-            let query = &self.input;
-            let mut scored: Vec<(usize, i64)> = self
-                .commands
-                .iter()
-                .enumerate()
-                .filter_map(|(idx, cmd)| {
-                    let path_score = self.matcher.fuzzy_match(&cmd.path, query);
-                    let cmd_score = self.matcher.fuzzy_match(&cmd.command, query);
-                    let explanation_score = self.matcher.fuzzy_match(&cmd.explanation, query);
-
-                    let best_score = [path_score, cmd_score, explanation_score]
-                        .into_iter()
-                        .flatten()
-                        .max();
-
-                    best_score.map(|score| (idx, score))
-                })
+            // Appending to the query can only narrow skim matches, so rescore
+            // just the current results; anything else rescans everything.
+            // Reaching NOTES_MIN_QUERY starts matching notes, which can widen them.
+            let crosses_notes = self.filter_query.chars().count() < NOTES_MIN_QUERY
+                && self.input.chars().count() >= NOTES_MIN_QUERY;
+            let narrowing = !self.filter_query.is_empty()
+                && !crosses_notes
+                && self.input.len() > self.filter_query.len()
+                && self.input.starts_with(&self.filter_query);
+            let candidates: Vec<usize> = if narrowing {
+                self.filtered.iter().map(|&(idx, _)| idx).collect()
+            } else {
+                (0..self.commands.len()).collect()
+            };
+
+            let mut scored: Vec<(usize, i64)> = candidates
+                .into_iter()
+                .filter_map(|idx| self.score(&self.commands[idx]).map(|score| (idx, score)))
                 .collect();
 
-            scored.sort_by(|a, b| b.1.cmp(&a.1));
+            // Sort by index first so ties keep store order either way
+            scored.sort_by_key(|s| s.0);
+            scored.sort_by_key(|s| std::cmp::Reverse(s.1));
             self.filtered = scored;
         }
+        self.filter_query = self.input.clone();
 
         if self.selected >= self.filtered.len() {
             self.selected = 0;
@@ -110,6 +225,25 @@ impl App {
         self.scroll_offset = 0;
     }
 
+    /// Rescore from scratch, e.g. after the command list itself changed.
+    fn refresh_filter(&mut self) {
+        self.filter_query.clear();
+        self.update_filter();
+    }
+
+    fn score(&self, cmd: &Command) -> Option<i64> {
+        let query = &self.input;
+        [
+            self.matcher.fuzzy_match(&cmd.path, query),
+            self.matcher.fuzzy_match(&cmd.command, query),
+            self.matcher.fuzzy_match(&cmd.explanation, query),
+            notes_score(&self.matcher, cmd, query),
+        ]
+        .into_iter()
+        .flatten()
+        .max()
+    }
+
     fn ensure_visible(&mut self) {
         if self.selected < self.scroll_offset {
             self.scroll_offset = self.selected;
@@ -140,10 +274,33 @@ impl App {
     pub fn select_current(&mut self) {
         if let Some(&(idx, _)) = self.filtered.get(self.selected) {
             self.selected_command = Some(self.commands[idx].clone());
+            // Enter without marks takes just the highlighted command
+            if self.multi && self.marked.is_empty() {
+                self.marked.insert(self.commands[idx].path.clone());
+            }
         }
         self.should_quit = true;
     }
 
+    /// Mark or unmark the highlighted command (multi-select mode).
+    pub fn toggle_mark(&mut self) {
+        if let Some(&(idx, _)) = self.filtered.get(self.selected) {
+            let path = &self.commands[idx].path;
+            if !self.marked.remove(path) {
+                self.marked.insert(path.clone());
+            }
+        }
+    }
+
+    /// Marked commands still in the list, in store order.
+    pub fn marked_commands(&self) -> Vec<Command> {
+        self.commands
+            .iter()
+            .filter(|cmd| self.marked.contains(&cmd.path))
+            .cloned()
+            .collect()
+    }
+
     pub fn cancel(&mut self) {
         match self.mode {
             Mode::Normal => self.should_quit = true,
@@ -166,23 +323,51 @@ impl App {
                 let field = self.get_active_field_mut();
                 field.push(c);
             }
+            Mode::Palette => {
+                self.palette_input.push(c);
+                self.palette_selected = 0;
+            }
             _ => {}
         }
     }
 
-    pub fn delete_char(&mut self) {
+    /// Insert pasted text; fields are single-line, so newlines become spaces.
+    pub fn paste(&mut self, text: &str) {
+        let text = text
+            .lines()
+            .map(str::trim_end)
+            .filter(|l| !l.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+
         match self.mode {
             Mode::Normal => {
-                if self.cursor_position > 0 {
-                    self.cursor_position -= 1;
-                    self.input.remove(self.cursor_position);
-                    self.update_filter();
-                }
+                self.input.insert_str(self.cursor_position, &text);
+                self.cursor_position += text.len();
+                self.update_filter();
+            }
+            Mode::Add | Mode::Edit => {
+                self.get_active_field_mut().push_str(&text);
+            }
+            _ => {}
+        }
+    }
+
+    pub fn delete_char(&mut self) {
+        match self.mode {
+            Mode::Normal if self.cursor_position > 0 => {
+                self.cursor_position -= 1;
+                self.input.remove(self.cursor_position);
+                self.update_filter();
             }
             Mode::Add | Mode::Edit => {
                 let field = self.get_active_field_mut();
                 field.pop();
             }
+            Mode::Palette => {
+                self.palette_input.pop();
+                self.palette_selected = 0;
+            }
             _ => {}
         }
     }
@@ -240,6 +425,13 @@ impl App {
         self.clear_form();
         self.active_field = InputField::Path;
         self.message = None;
+
+        // A search that found nothing is usually the path the user wanted
+        let query = self.input.trim();
+        if self.filtered.is_empty() && !query.is_empty() {
+            self.form_path = query.to_string();
+            self.active_field = InputField::Command;
+        }
     }
 
     pub fn enter_edit_mode(&mut self) {
@@ -262,6 +454,37 @@ impl App {
         }
     }
 
+    /// Show `message` for the next [`MESSAGE_DRAWS`] redraws.
+    pub fn set_message(&mut self, message: Message) {
+        self.message = Some(message);
+        self.message_draws = MESSAGE_DRAWS;
+    }
+
+    /// Count down a redraw, clearing the message once it has been shown enough.
+    pub fn tick_message(&mut self) {
+        self.message_draws = self.message_draws.saturating_sub(1);
+        if self.message_draws == 0 {
+            self.message = None;
+        }
+    }
+
+    pub fn toggle_scores(&mut self) {
+        self.show_scores = !self.show_scores;
+        let state = if self.show_scores { "shown" } else { "hidden" };
+        self.set_message(Message::Info(format!("Match scores {}", state)));
+    }
+
+    /// Char positions in `text` matched by the current query, for highlighting.
+    pub fn match_indices(&self, text: &str) -> Vec<usize> {
+        if self.input.is_empty() {
+            return vec![];
+        }
+        self.matcher
+            .fuzzy_indices(text, &self.input)
+            .map(|(_, indices)| indices)
+            .unwrap_or_default()
+    }
+
     pub fn toggle_help(&mut self) {
         self.mode = if self.mode == Mode::Help {
             Mode::Normal
@@ -270,6 +493,70 @@ impl App {
         };
     }
 
+    pub fn open_palette(&mut self) {
+        self.mode = Mode::Palette;
+        self.palette_input.clear();
+        self.palette_selected = 0;
+        self.message = None;
+    }
+
+    /// Palette actions matching the palette query, best first.
+    pub fn palette_matches(&self) -> Vec<(PaletteAction, &'static str, &'static str)> {
+        if self.palette_input.is_empty() {
+            return PALETTE_ACTIONS.to_vec();
+        }
+
+        let mut scored: Vec<_> = PALETTE_ACTIONS
+            .iter()
+            .filter_map(|&entry| {
+                self.matcher
+                    .fuzzy_match(entry.1, &self.palette_input)
+                    .map(|score| (entry, score))
+            })
+            .collect();
+        scored.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+        scored.into_iter().map(|(entry, _)| entry).collect()
+    }
+
+    pub fn palette_move(&mut self, down: bool) {
+        let count = self.palette_matches().len();
+        if down && self.palette_selected + 1 < count {
+            self.palette_selected += 1;
+        } else if !down && self.palette_selected > 0 {
+            self.palette_selected -= 1;
+        }
+    }
+
+    /// Run the highlighted palette action as if its key had been pressed.
+    pub fn run_palette_action(&mut self) {
+        let Some((action, _, _)) = self.palette_matches().get(self.palette_selected).copied() else {
+            return;
+        };
+
+        self.mode = Mode::Normal;
+        match action {
+            PaletteAction::Add => self.enter_add_mode(),
+            PaletteAction::Edit => self.enter_edit_mode(),
+            PaletteAction::Delete => self.enter_delete_mode(),
+            PaletteAction::Help => self.toggle_help(),
+            PaletteAction::ToggleScores => self.toggle_scores(),
+            PaletteAction::Run => {
+                self.pick_action = PickAction::Run;
+                self.select_current();
+            }
+            PaletteAction::CopyPath => {
+                if let Some(&(idx, _)) = self.filtered.get(self.selected) {
+                    let path = self.commands[idx].path.clone();
+                    self.set_message(if copy_to_clipboard(&path, &self.clipboard_tool) {
+                        Message::Success(format!("Copied {}", path))
+                    } else {
+                        Message::Warning("No clipboard tool available".to_string())
+                    });
+                }
+            }
+        }
+    }
+
     pub fn next_field(&mut self) {
         self.active_field = match self.active_field {
             InputField::Path => InputField::Command,
@@ -301,7 +588,7 @@ impl App {
         self.editing_original_path = None;
     }
 
-    pub fn confirm_action(&mut self, store: &Store) {
+    pub fn confirm_action(&mut self, store: &dyn CommandStore) {
         match self.mode {
             Mode::Add => self.save_new_command(store),
             Mode::Edit => self.save_edited_command(store),
@@ -310,30 +597,34 @@ impl App {
         }
     }
 
-    fn save_new_command(&mut self, store: &Store) {
+    fn save_new_command(&mut self, store: &dyn CommandStore) {
         if self.form_path.is_empty() || self.form_command.is_empty() {
-            self.message = Some(("Path and command are required".to_string(), true));
+            self.set_message(Message::Warning("Path and command are required".to_string()));
             return;
         }
 
-        let cmd = Command::new(&self.form_path, &self.form_command, &self.form_description);
+        let mut cmd = Command::new(&self.form_path, &self.form_command, &self.form_description);
+        cmd.author = self.author.clone();
+        cmd.created = format_timestamp(now());
         match store.add(&cmd, false) {
             Ok(()) => {
+                let message = Message::Success(format!("Added {}", cmd.path));
+                self.changes.added.push(cmd.path.clone());
                 self.commands.push(cmd);
-                self.update_filter();
+                self.refresh_filter();
                 self.mode = Mode::Normal;
                 self.clear_form();
-                self.message = Some(("Command added successfully".to_string(), false));
+                self.set_message(message);
             }
             Err(e) => {
-                self.message = Some((format!("Error: {}", e), true));
+                self.set_message(Message::Error(e.to_string()));
             }
         }
     }
 
-    fn save_edited_command(&mut self, store: &Store) {
+    fn save_edited_command(&mut self, store: &dyn CommandStore) {
         if self.form_path.is_empty() || self.form_command.is_empty() {
-            self.message = Some(("Path and command are required".to_string(), true));
+            self.set_message(Message::Warning("Path and command are required".to_string()));
             return;
         }
 
@@ -341,49 +632,67 @@ impl App {
         
         // Remove old command
         if let Err(e) = store.remove(&original_path) {
-            self.message = Some((format!("Error: {}", e), true));
+            self.set_message(Message::Error(e.to_string()));
             return;
         }
 
-        // Add updated command
-        let cmd = Command::new(&self.form_path, &self.form_command, &self.form_description);
+        // Add updated command, keeping fields the form doesn't edit
+        let original = self.commands.iter().find(|c| c.path == original_path).cloned();
+        let mut cmd = Command::new(&self.form_path, &self.form_command, &self.form_description);
+        if let Some(original) = &original {
+            cmd = Command {
+                path: cmd.path,
+                command: cmd.command,
+                explanation: cmd.explanation,
+                ..original.clone()
+            };
+        }
         match store.add(&cmd, false) {
             Ok(()) => {
+                let message = Message::Success(format!("Updated {}", cmd.path));
+                self.changes.edited.push(if cmd.path == original_path {
+                    cmd.path.clone()
+                } else {
+                    format!("{} → {}", original_path, cmd.path)
+                });
                 // Update in-memory list
                 if let Some(idx) = self.commands.iter().position(|c| c.path == original_path) {
                     self.commands[idx] = cmd;
                 }
-                self.update_filter();
+                self.refresh_filter();
                 self.mode = Mode::Normal;
                 self.clear_form();
-                self.message = Some(("Command updated successfully".to_string(), false));
+                self.set_message(message);
             }
             Err(e) => {
                 // Try to restore old command on failure
-                let _ = store.add(
-                    &Command::new(&original_path, &self.form_command, &self.form_description),
-                    true,
-                );
-                self.message = Some((format!("Error: {}", e), true));
+                if let Some(original) = &original {
+                    let _ = store.add(original, true);
+                }
+                self.set_message(Message::Error(e.to_string()));
             }
         }
     }
 
-    fn delete_selected_command(&mut self, store: &Store) {
+    fn delete_selected_command(&mut self, store: &dyn CommandStore) {
         if let Some(&(idx, _)) = self.filtered.get(self.selected) {
             let path = self.commands[idx].path.clone();
-            match store.remove(&path) {
+            let removed = Trash::new()
+                .put(&self.commands[idx])
+                .and_then(|()| store.remove(&path));
+            match removed {
                 Ok(()) => {
+                    self.changes.deleted.push(path.clone());
                     self.commands.remove(idx);
-                    self.update_filter();
+                    self.refresh_filter();
                     if self.selected >= self.filtered.len() && self.selected > 0 {
                         self.selected -= 1;
                     }
                     self.mode = Mode::Normal;
-                    self.message = Some(("Command deleted".to_string(), false));
+                    self.set_message(Message::Success(format!("Deleted {}", path)));
                 }
                 Err(e) => {
-                    self.message = Some((format!("Error: {}", e), true));
+                    self.set_message(Message::Error(e.to_string()));
                     self.mode = Mode::Normal;
                 }
             }
@@ -391,45 +700,97 @@ impl App {
     }
 }
 
-pub fn run(commands: Vec<Command>) -> Result<Option<Command>> {
+/// Puts the terminal into TUI mode and restores it when dropped.
+///
+/// Restoration also runs from a panic hook, since release builds use
+/// `panic = "abort"` and never unwind into `Drop`.
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn enter() -> Result<Self> {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            restore_terminal();
+            previous(info);
+        }));
+
+        // Created before any terminal change so early errors still clean up
+        let guard = Self;
+        enable_raw_mode().map_err(|e| CmdxError::Tui(e.to_string()))?;
+        execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture, EnableBracketedPaste)
+            .map_err(|e| CmdxError::Tui(e.to_string()))?;
+        Ok(guard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        restore_terminal();
+        // Back to the default hook now that the terminal is ours again
+        let _ = panic::take_hook();
+    }
+}
+
+fn restore_terminal() {
+    let _ = disable_raw_mode();
+    let _ = execute!(
+        io::stdout(),
+        LeaveAlternateScreen,
+        DisableMouseCapture,
+        DisableBracketedPaste,
+        Show
+    );
+}
+
+pub fn run(commands: Vec<Command>, initial_query: &str) -> Result<Option<(Command, PickAction)>> {
+    run_session(commands, initial_query, false).map(|session| session.picked)
+}
+
+/// Like [`run`], but also reports the adds, edits and deletes made in the
+/// TUI. With `multi`, Tab marks commands for [`Session::marked`].
+pub fn run_session(commands: Vec<Command>, initial_query: &str, multi: bool) -> Result<Session> {
     let config = Config::load().unwrap_or_default();
-    let store = Store::new(&config);
+    let store = store::open(&config)?;
 
-    enable_raw_mode().map_err(|e| CmdxError::Tui(e.to_string()))?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)
-        .map_err(|e| CmdxError::Tui(e.to_string()))?;
-    let backend = CrosstermBackend::new(stdout);
+    let _guard = TerminalGuard::enter()?;
+    let backend = CrosstermBackend::new(io::stdout());
     let mut terminal = Terminal::new(backend).map_err(|e| CmdxError::Tui(e.to_string()))?;
 
     let mut app = App::new(commands);
+    app.multi = multi;
+    app.icons = config.display.icons.clone();
+    app.list_format = config.display.list_format.clone();
+    app.author = config.author();
+    app.clipboard_tool = config.clipboard.tool.clone();
+    if app.list_format.contains("{count") {
+        app.use_counts = history::use_counts();
+    }
+    if !initial_query.is_empty() {
+        app.paste(initial_query);
+    }
 
-    let result = loop {
+    loop {
         terminal
             .draw(|f| draw_ui(f, &mut app))
             .map_err(|e| CmdxError::Tui(e.to_string()))?;
+        app.tick_message();
 
-        if let Event::Key(key) = event::read().map_err(|e| CmdxError::Tui(e.to_string()))? {
-            handle_key_event(&mut app, key, &store);
+        match event::read().map_err(|e| CmdxError::Tui(e.to_string()))? {
+            Event::Key(key) => handle_key_event(&mut app, key, store.as_ref()),
+            Event::Paste(text) => app.paste(&text),
+            _ => {}
         }
 
         if app.should_quit {
-            break app.selected_command.clone();
+            // Cancelling drops the marks along with the pick
+            let marked = if app.selected_command.is_some() { app.marked_commands() } else { Vec::new() };
+            return Ok(Session {
+                picked: app.selected_command.clone().map(|cmd| (cmd, app.pick_action)),
+                marked,
+                changes: app.changes,
+            });
         }
-    };
-
-    disable_raw_mode().map_err(|e| CmdxError::Tui(e.to_string()))?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )
-    .map_err(|e| CmdxError::Tui(e.to_string()))?;
-    terminal
-        .show_cursor()
-        .map_err(|e| CmdxError::Tui(e.to_string()))?;
-
-    Ok(result)
+    }
 }
 
 #[cfg(test)]
@@ -445,6 +806,57 @@ mod tests {
         ]
     }
 
+    #[test]
+    fn test_palette_filters_and_runs_action() {
+        let mut app = App::new(sample_commands());
+        app.open_palette();
+        assert_eq!(app.palette_matches().len(), PALETTE_ACTIONS.len());
+
+        for c in "edt".chars() {
+            app.insert_char(c);
+        }
+        assert_eq!(app.palette_matches()[0].0, PaletteAction::Edit);
+
+        app.run_palette_action();
+        assert_eq!(app.mode, Mode::Edit);
+        assert_eq!(app.form_path, "git/status");
+    }
+
+    #[test]
+    fn test_change_log_records_adds_and_renames() {
+        let store = crate::store::MemoryStore::default();
+        let mut app = App::new(sample_commands());
+        for cmd in &app.commands {
+            store.add(cmd, false).unwrap();
+        }
+        assert!(app.changes.is_empty());
+
+        app.mode = Mode::Add;
+        app.form_path = "k8s/pods".to_string();
+        app.form_command = "kubectl get pods".to_string();
+        app.confirm_action(&store);
+
+        app.mode = Mode::Edit;
+        app.editing_original_path = Some("git/commit".to_string());
+        app.form_path = "git/ci".to_string();
+        app.form_command = "git commit -m".to_string();
+        app.confirm_action(&store);
+
+        assert_eq!(app.changes.added, vec!["k8s/pods"]);
+        assert_eq!(app.changes.edited, vec!["git/commit → git/ci"]);
+        assert!(app.changes.deleted.is_empty());
+    }
+
+    #[test]
+    fn test_match_indices() {
+        let mut app = App::new(sample_commands());
+        assert!(app.match_indices("docker/ps").is_empty());
+
+        app.input = "dps".to_string();
+        assert_eq!(app.match_indices("docker/ps"), vec![0, 7, 8]);
+        assert!(app.match_indices("git/status").is_empty());
+    }
+
     #[test]
     fn test_app_new() {
         let commands = sample_commands();
@@ -469,6 +881,31 @@ mod tests {
         assert_eq!(app.filtered.len(), 2);
     }
 
+    #[test]
+    fn test_incremental_filter_matches_full_rescore() {
+        let mut commands = sample_commands();
+        commands.push(Command::new("git/log", "git log --oneline", "Compact history"));
+        commands.push(Command::new("docker/logs", "docker logs -f", "Follow container logs"));
+        // Only reachable through its notes, which need a 3-character query
+        let mut uptime = Command::new("sys/up", "uptime", "Load averages");
+        uptime.output = "12:00 up 3 days, xyz load 0.1".to_string();
+        commands.push(uptime);
+        let mut app = App::new(commands.clone());
+
+        // Type, backspace and retype to exercise both paths
+        for step in ["l", "lo", "log", "logs", "log", "loG", "lo", "o", "on", "x", "xy", "xyz"] {
+            app.input = step.to_string();
+            app.cursor_position = step.len();
+            app.update_filter();
+
+            let mut full = App::new(commands.clone());
+            full.input = step.to_string();
+            full.update_filter();
+
+            assert_eq!(app.filtered, full.filtered, "query {:?}", step);
+        }
+    }
+
     #[test]
     fn test_filter_by_description() {
         let mut app = App::new(sample_commands());
@@ -566,6 +1003,19 @@ mod tests {
         assert_eq!(app.active_field, InputField::Path);
     }
 
+    #[test]
+    fn test_enter_add_mode_prefills_unmatched_query() {
+        let mut app = App::new(sample_commands());
+        app.input = "net/ports".to_string();
+        app.update_filter();
+        assert!(app.filtered.is_empty());
+
+        app.enter_add_mode();
+
+        assert_eq!(app.form_path, "net/ports");
+        assert_eq!(app.active_field, InputField::Command);
+    }
+
     #[test]
     fn test_enter_edit_mode() {
         let mut app = App::new(sample_commands());
@@ -656,6 +1106,21 @@ mod tests {
         assert_eq!(app.form_command, "cmd");
     }
 
+    #[test]
+    fn test_paste_collapses_newlines() {
+        let mut app = App::new(sample_commands());
+
+        app.paste("docker");
+        assert_eq!(app.input, "docker");
+        assert_eq!(app.cursor_position, 6);
+        assert_eq!(app.filtered.len(), 2);
+
+        app.enter_add_mode();
+        app.next_field();
+        app.paste("docker run \\\r\n  --rm alpine\n");
+        assert_eq!(app.form_command, "docker run \\   --rm alpine");
+    }
+
     #[test]
     fn test_form_delete_char() {
         let mut app = App::new(sample_commands());
@@ -775,4 +1240,58 @@ mod tests {
         // Selected should reset when filter changes
         assert_eq!(app.selected, 0);
     }
+
+    #[test]
+    fn test_messages_use_levels_and_time_out() {
+        let store = crate::store::MemoryStore::default();
+        let mut app = App::new(sample_commands());
+        for cmd in &app.commands {
+            store.add(cmd, false).unwrap();
+        }
+
+        app.mode = Mode::Add;
+        app.confirm_action(&store);
+        assert!(matches!(app.message, Some(Message::Warning(_))));
+        assert_eq!(app.mode, Mode::Add);
+
+        app.form_path = "git/status".to_string();
+        app.form_command = "git status -s".to_string();
+        app.confirm_action(&store);
+        assert!(matches!(app.message, Some(Message::Error(_))));
+
+        app.mode = Mode::Delete;
+        app.confirm_action(&store);
+        assert_eq!(app.message, Some(Message::Success("Deleted git/status".to_string())));
+
+        for _ in 1..MESSAGE_DRAWS {
+            app.tick_message();
+        }
+        assert!(app.message.is_some());
+        app.tick_message();
+        assert!(app.message.is_none());
+    }
+
+    #[test]
+    fn test_multi_select_marks() {
+        let mut app = App::new(sample_commands());
+        app.multi = true;
+        app.toggle_mark();
+        app.move_down();
+        app.move_down();
+        app.toggle_mark();
+        app.toggle_mark();
+        app.move_down();
+        app.toggle_mark();
+        app.select_current();
+
+        let paths: Vec<String> = app.marked_commands().into_iter().map(|c| c.path).collect();
+        assert_eq!(paths, vec!["git/status", "docker/prune"]);
+
+        // Enter with nothing marked picks the highlighted command
+        let mut app = App::new(sample_commands());
+        app.multi = true;
+        app.move_down();
+        app.select_current();
+        assert_eq!(app.marked_commands()[0].path, "git/commit");
+    }
 }
@@ -1,14 +1,15 @@
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
 use super::app::{App, Mode};
-use crate::store::Store;
+use crate::store::CommandStore;
 
-pub fn handle_key_event(app: &mut App, key: KeyEvent, store: &Store) {
+pub fn handle_key_event(app: &mut App, key: KeyEvent, store: &dyn CommandStore) {
     match app.mode {
         Mode::Normal => handle_normal_mode(app, key),
         Mode::Add | Mode::Edit => handle_form_mode(app, key, store),
         Mode::Delete => handle_delete_mode(app, key, store),
         Mode::Help => handle_help_mode(app, key),
+        Mode::Palette => handle_palette_mode(app, key),
     }
 }
 
@@ -31,6 +32,15 @@ fn handle_normal_mode(app: &mut App, key: KeyEvent) {
         (KeyCode::Down, _) | (KeyCode::Char('j'), KeyModifiers::CONTROL) => {
             app.move_down();
         }
+        // Multi-select: mark and move on, like fzf
+        (KeyCode::Tab, KeyModifiers::NONE) if app.multi => {
+            app.toggle_mark();
+            app.move_down();
+        }
+        (KeyCode::BackTab, _) if app.multi => {
+            app.toggle_mark();
+            app.move_up();
+        }
         (KeyCode::Tab, KeyModifiers::NONE) => {
             app.move_down();
         }
@@ -48,9 +58,19 @@ fn handle_normal_mode(app: &mut App, key: KeyEvent) {
         (KeyCode::F(4), _) => {
             app.enter_delete_mode();
         }
+        (KeyCode::F(5), _) => {
+            app.toggle_scores();
+        }
         (KeyCode::F(1), _) => {
             app.toggle_help();
         }
+        (KeyCode::Char('p'), KeyModifiers::CONTROL) => {
+            app.open_palette();
+        }
+        // `:` only opens the palette before anything is typed, so it can still be searched for
+        (KeyCode::Char(':'), KeyModifiers::NONE | KeyModifiers::SHIFT) if app.input.is_empty() => {
+            app.open_palette();
+        }
 
         // Cursor movement
         (KeyCode::Left, _) | (KeyCode::Char('b'), KeyModifiers::CONTROL) => {
@@ -86,7 +106,7 @@ fn handle_normal_mode(app: &mut App, key: KeyEvent) {
     }
 }
 
-fn handle_form_mode(app: &mut App, key: KeyEvent, store: &Store) {
+fn handle_form_mode(app: &mut App, key: KeyEvent, store: &dyn CommandStore) {
     match (key.code, key.modifiers) {
         (KeyCode::Esc, _) => {
             app.cancel();
@@ -113,7 +133,7 @@ fn handle_form_mode(app: &mut App, key: KeyEvent, store: &Store) {
     }
 }
 
-fn handle_delete_mode(app: &mut App, key: KeyEvent, store: &Store) {
+fn handle_delete_mode(app: &mut App, key: KeyEvent, store: &dyn CommandStore) {
     match key.code {
         KeyCode::Char('y') | KeyCode::Char('Y') => {
             app.confirm_action(store);
@@ -133,3 +153,27 @@ fn handle_help_mode(app: &mut App, key: KeyEvent) {
         _ => {}
     }
 }
+
+fn handle_palette_mode(app: &mut App, key: KeyEvent) {
+    match (key.code, key.modifiers) {
+        (KeyCode::Esc, _) | (KeyCode::Char('c'), KeyModifiers::CONTROL) => {
+            app.cancel();
+        }
+        (KeyCode::Enter, _) => {
+            app.run_palette_action();
+        }
+        (KeyCode::Up, _) | (KeyCode::Char('k'), KeyModifiers::CONTROL) => {
+            app.palette_move(false);
+        }
+        (KeyCode::Down, _) | (KeyCode::Char('j'), KeyModifiers::CONTROL) => {
+            app.palette_move(true);
+        }
+        (KeyCode::Backspace, _) => {
+            app.delete_char();
+        }
+        (KeyCode::Char(c), KeyModifiers::NONE | KeyModifiers::SHIFT) => {
+            app.insert_char(c);
+        }
+        _ => {}
+    }
+}
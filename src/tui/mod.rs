@@ -2,4 +2,4 @@ mod app;
 mod event;
 mod ui;
 
-pub use app::run;
+pub use app::{run, run_session, ChangeLog, PickAction};
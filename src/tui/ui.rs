@@ -1,15 +1,21 @@
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Modifier, Style},
+    style::{Color, Modifier, Style},
     text::{Line, Span},
     widgets::{
         Block, Borders, Clear, List, ListItem, Paragraph, Scrollbar,
-        ScrollbarOrientation, ScrollbarState, Wrap,
+        ScrollbarOrientation, ScrollbarState,
     },
     Frame,
 };
 
-use super::app::{App, InputField, Mode};
+use super::app::{App, InputField, Message, Mode};
+use crate::command::{split_placeholders, Command};
+use crate::text::wrap_text;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Lines of command text shown in the delete confirmation.
+const DELETE_PREVIEW_LINES: usize = 3;
 
 #[allow(dead_code)]
 mod cat {
@@ -75,6 +81,7 @@ pub fn draw_ui(f: &mut Frame, app: &mut App) {
         Mode::Add | Mode::Edit => draw_form_modal(f, app, size),
         Mode::Delete => draw_delete_modal(f, app, size),
         Mode::Help => draw_help_modal(f, size),
+        Mode::Palette => draw_palette_modal(f, app, size),
         Mode::Normal => {}
     }
 }
@@ -85,7 +92,7 @@ fn draw_search_bar(f: &mut Frame, app: &App, area: Rect) {
 
     let layout = Layout::default()
         .direction(Direction::Horizontal)
-        .constraints([Constraint::Min(1), Constraint::Length(15)])
+        .constraints([Constraint::Min(1), Constraint::Length(20)])
         .split(area);
 
     // Prompt
@@ -98,18 +105,21 @@ fn draw_search_bar(f: &mut Frame, app: &App, area: Rect) {
     f.render_widget(Paragraph::new(line), layout[0]);
 
     // Count
-    let count = Line::from(vec![
+    let mut count = Line::from(vec![
         Span::styled(":", Style::default().fg(cat::OVERLAY0)),
         Span::styled(format!(" {}", filtered), Style::default().fg(cat::BLUE)),
         Span::styled("/", Style::default().fg(cat::OVERLAY0)),
         Span::styled(format!("{}", total), Style::default().fg(cat::OVERLAY1)),
     ]);
+    if !app.marked.is_empty() {
+        count.spans.push(Span::styled(format!(" ●{}", app.marked.len()), Style::default().fg(cat::GREEN)));
+    }
     f.render_widget(Paragraph::new(count).alignment(Alignment::Right), layout[1]);
 }
 
 fn draw_command_list(f: &mut Frame, app: &mut App, area: Rect) {
     // Title bar with dashes
-    let title = format!("─ Commands ─");
+    let title = "─ Commands ─".to_string();
     let title_line = Line::from(vec![
         Span::styled(title, Style::default().fg(cat::OVERLAY1)),
         Span::styled(
@@ -125,46 +135,49 @@ fn draw_command_list(f: &mut Frame, app: &mut App, area: Rect) {
         .style(Style::default().bg(cat::BASE));
 
     f.render_widget(block.clone(), area);
-    let inner = Rect {
-        x: area.x,
-        y: area.y + 1,
-        width: area.width,
-        height: area.height.saturating_sub(1),
-    };
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1), // Title
+            Constraint::Min(1),    // List
+            Constraint::Length(1), // Status line
+        ])
+        .split(area);
+    let inner = chunks[1];
+
+    draw_status_line(f, app, chunks[2]);
 
     let visible_items = inner.height as usize;
     app.set_visible_height(visible_items.max(1));
 
     let visible_range = app.visible_range();
+    let columns = parse_list_format(&app.list_format);
+    let max_width = inner.width.saturating_sub(2) as usize;
 
     let items: Vec<ListItem> = visible_range
         .clone()
         .map(|filtered_idx| {
-            let (cmd_idx, _score) = app.filtered[filtered_idx];
+            let (cmd_idx, score) = app.filtered[filtered_idx];
             let cmd = &app.commands[cmd_idx];
             let is_selected = filtered_idx == app.selected;
 
-            // Get icon and color based on category
-            let (icon, icon_color) = get_category_icon(&cmd.path);
-            let max_width = inner.width.saturating_sub(4) as usize;
-            let path_display = truncate_str(&cmd.path, max_width);
-
-            let line = if is_selected {
-                Line::from(vec![
-                    Span::styled(icon, Style::default().fg(icon_color)),
-                    Span::styled(" ", Style::default()),
-                    Span::styled(
-                        path_display,
-                        Style::default().fg(cat::TEXT).add_modifier(Modifier::BOLD),
-                    ),
-                ])
-            } else {
-                Line::from(vec![
-                    Span::styled(icon, Style::default().fg(icon_color)),
-                    Span::styled(" ", Style::default()),
-                    Span::styled(path_display, Style::default().fg(cat::SUBTEXT0)),
-                ])
-            };
+            let text_color = if is_selected { cat::TEXT } else { cat::SUBTEXT0 };
+            // Score goes at the far right so it doesn't shift the columns
+            let badge = (app.show_scores && !app.input.is_empty()).then(|| format!(" {}", score));
+            let mark = app.multi.then(|| if app.marked.contains(&cmd.path) { "● " } else { "  " });
+            let width = max_width
+                .saturating_sub(badge.as_ref().map_or(0, |b| b.width()))
+                .saturating_sub(mark.map_or(0, |m| m.width()));
+            let mut spans = list_row_spans(app, cmd, &columns, width, text_color, is_selected);
+            if let Some(mark) = mark {
+                spans.insert(0, Span::styled(mark, Style::default().fg(cat::GREEN)));
+            }
+            if let Some(badge) = badge {
+                let used: usize = spans.iter().map(|s| s.content.width()).sum();
+                spans.push(Span::raw(" ".repeat(width.saturating_sub(used))));
+                spans.push(Span::styled(badge, Style::default().fg(cat::OVERLAY0)));
+            }
+            let line = Line::from(spans);
 
             if is_selected {
                 ListItem::new(line).style(Style::default().bg(cat::SURFACE0))
@@ -194,6 +207,51 @@ fn draw_command_list(f: &mut Frame, app: &mut App, area: Rect) {
     }
 }
 
+/// Selected command's text, truncated, so it can be read while scrolling.
+/// A pending message takes its place until it times out.
+fn draw_status_line(f: &mut Frame, app: &App, area: Rect) {
+    if let Some(message) = &app.message {
+        f.render_widget(
+            Paragraph::new(message_line(message, area.width as usize)).style(Style::default().bg(cat::MANTLE)),
+            area,
+        );
+        return;
+    }
+
+    let line = match app.filtered.get(app.selected) {
+        Some(&(idx, _)) => Line::from(vec![
+            Span::styled("$ ", Style::default().fg(cat::OVERLAY0)),
+            Span::styled(
+                truncate_str(&app.commands[idx].command, area.width.saturating_sub(2) as usize),
+                Style::default().fg(cat::SUBTEXT1),
+            ),
+        ]),
+        None if !app.input.trim().is_empty() => Line::from(vec![
+            Span::styled("F2", Style::default().fg(cat::PEACH)),
+            Span::styled(
+                truncate_str(&format!(" add {}", app.input.trim()), area.width.saturating_sub(2) as usize),
+                Style::default().fg(cat::OVERLAY1),
+            ),
+        ]),
+        None => Line::from(""),
+    };
+    f.render_widget(Paragraph::new(line).style(Style::default().bg(cat::MANTLE)), area);
+}
+
+/// A message with a level marker, colored by level.
+fn message_line(message: &Message, width: usize) -> Line<'static> {
+    let (marker, color) = match message {
+        Message::Info(_) => ("i ", cat::BLUE),
+        Message::Success(_) => ("✓ ", cat::GREEN),
+        Message::Warning(_) => ("! ", cat::YELLOW),
+        Message::Error(_) => ("✗ ", cat::RED),
+    };
+    Line::from(vec![
+        Span::styled(marker, Style::default().fg(color).add_modifier(Modifier::BOLD)),
+        Span::styled(truncate_str(message.text(), width.saturating_sub(2)), Style::default().fg(color)),
+    ])
+}
+
 fn draw_preview(f: &mut Frame, app: &App, area: Rect) {
     // Get selected command for title
     let title_text = if let Some(&(idx, _)) = app.filtered.get(app.selected) {
@@ -235,13 +293,51 @@ fn draw_preview(f: &mut Frame, app: &App, area: Rect) {
         // Command with line numbers (syntax highlight style)
         let cmd_lines = wrap_text(&cmd.command, inner.width.saturating_sub(line_num_width + 2) as usize);
         for (i, line) in cmd_lines.iter().enumerate() {
+            let mut spans = vec![Span::styled(
+                format!("{:>width$}  ", i + 1, width = line_num_width as usize),
+                Style::default().fg(cat::OVERLAY0),
+            )];
+            // Highlight {placeholders} so templates stand out
+            for (text, is_placeholder) in split_placeholders(line) {
+                let style = if is_placeholder {
+                    Style::default().fg(cat::PEACH).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(cat::TEXT)
+                };
+                spans.push(Span::styled(text, style));
+            }
+            lines.push(Line::from(spans));
+        }
+
+        let placeholders = cmd.placeholders();
+        let gutter = || {
+            Span::styled(
+                format!("{:>width$}  ", "{}", width = line_num_width as usize),
+                Style::default().fg(cat::OVERLAY0),
+            )
+        };
+        if !placeholders.is_empty() && cmd.args.is_empty() {
             lines.push(Line::from(vec![
-                Span::styled(
-                    format!("{:>width$}  ", i + 1, width = line_num_width as usize),
-                    Style::default().fg(cat::OVERLAY0),
-                ),
-                Span::styled(line, Style::default().fg(cat::TEXT)),
+                gutter(),
+                Span::styled(placeholders.join(", "), Style::default().fg(cat::PEACH)),
             ]));
+        } else {
+            // One line per placeholder, with its `## args` description as a hint
+            for name in placeholders {
+                let mut spans = vec![gutter(), Span::styled(name, Style::default().fg(cat::PEACH))];
+                if let Some(arg) = cmd.arg(name) {
+                    if !arg.required {
+                        spans.push(Span::styled("?", Style::default().fg(cat::OVERLAY0)));
+                    }
+                    if !arg.description.is_empty() {
+                        spans.push(Span::styled(
+                            format!("  {}", arg.description),
+                            Style::default().fg(cat::SUBTEXT0),
+                        ));
+                    }
+                }
+                lines.push(Line::from(spans));
+            }
         }
 
         // Empty line
@@ -261,6 +357,20 @@ fn draw_preview(f: &mut Frame, app: &App, area: Rect) {
             }
         }
 
+        // Example output, dimmed and unwrapped to keep its layout
+        if !cmd.output.is_empty() {
+            lines.push(Line::from(""));
+            for line in cmd.output.lines() {
+                lines.push(Line::from(vec![
+                    Span::styled(
+                        format!("{:>width$}  ", ">", width = line_num_width as usize),
+                        Style::default().fg(cat::OVERLAY0),
+                    ),
+                    Span::styled(line, Style::default().fg(cat::OVERLAY1)),
+                ]));
+            }
+        }
+
         // Help hints at bottom
         lines.push(Line::from(""));
         lines.push(Line::from(vec![
@@ -284,8 +394,33 @@ fn draw_preview(f: &mut Frame, app: &App, area: Rect) {
     }
 }
 
-fn get_category_icon(path: &str) -> (&'static str, ratatui::style::Color) {
+fn get_category_icon(path: &str, style: &str) -> (&'static str, ratatui::style::Color) {
     let category = path.split('/').next().unwrap_or("");
+    match style {
+        "none" => ("", cat::OVERLAY1),
+        "emoji" => get_category_emoji(category),
+        _ => get_category_nerd_icon(category),
+    }
+}
+
+fn get_category_emoji(category: &str) -> (&'static str, ratatui::style::Color) {
+    match category {
+        "git" => ("🌿", cat::PEACH),
+        "docker" => ("🐳", cat::BLUE),
+        "pg" | "postgres" | "db" => ("🐘", cat::SAPPHIRE),
+        "npm" | "node" => ("📦", cat::GREEN),
+        "cargo" | "rust" => ("🦀", cat::PEACH),
+        "k8s" | "kubectl" => ("⎈", cat::LAVENDER),
+        "sys" | "linux" => ("🐧", cat::YELLOW),
+        "net" | "network" => ("🌐", cat::TEAL),
+        "ssh" => ("🔑", cat::MAUVE),
+        "dev" => ("🛠", cat::PINK),
+        "files" => ("📁", cat::ROSEWATER),
+        _ => ("▸", cat::OVERLAY1),
+    }
+}
+
+fn get_category_nerd_icon(category: &str) -> (&'static str, ratatui::style::Color) {
     match category {
         "git" => ("", cat::PEACH),
         "docker" => ("󰡨", cat::BLUE),
@@ -338,6 +473,11 @@ fn draw_form_modal(f: &mut Frame, app: &App, size: Rect) {
     draw_form_field(f, "command", &app.form_command, app.active_field == InputField::Command, chunks[1]);
     draw_form_field(f, "description", &app.form_description, app.active_field == InputField::Description, chunks[2]);
 
+    // Validation and save errors keep the form open, so show them here
+    if let Some(message) = &app.message {
+        f.render_widget(Paragraph::new(message_line(message, chunks[3].width as usize)), chunks[3]);
+    }
+
     let hints = Line::from(vec![
         Span::styled("Tab", Style::default().fg(cat::OVERLAY1)),
         Span::styled(" next  ", Style::default().fg(cat::OVERLAY0)),
@@ -380,7 +520,7 @@ fn draw_form_field(f: &mut Frame, label: &str, value: &str, is_active: bool, are
 }
 
 fn draw_delete_modal(f: &mut Frame, app: &App, size: Rect) {
-    let modal_area = centered_rect(40, 20, size);
+    let modal_area = centered_rect(50, 30, size);
 
     f.render_widget(Clear, modal_area);
 
@@ -392,29 +532,33 @@ fn draw_delete_modal(f: &mut Frame, app: &App, size: Rect) {
 
     f.render_widget(block.clone(), modal_area);
     let inner = block.inner(modal_area);
+    let width = inner.width.saturating_sub(4) as usize;
 
-    let cmd_name = if let Some(&(idx, _)) = app.filtered.get(app.selected) {
-        truncate_str(&app.commands[idx].path, inner.width.saturating_sub(4) as usize)
-    } else {
-        String::new()
-    };
-
-    let content = vec![
-        Line::from(""),
-        Line::from(Span::styled(cmd_name, Style::default().fg(cat::YELLOW))),
-        Line::from(""),
-        Line::from(vec![
-            Span::styled("y", Style::default().fg(cat::RED)),
-            Span::styled(" yes  ", Style::default().fg(cat::OVERLAY0)),
-            Span::styled("n", Style::default().fg(cat::OVERLAY1)),
-            Span::styled(" no", Style::default().fg(cat::OVERLAY0)),
-        ]),
-    ];
-
-    f.render_widget(
-        Paragraph::new(content).alignment(Alignment::Center).wrap(Wrap { trim: true }),
-        inner,
-    );
+    let mut content = vec![Line::from("")];
+    if let Some(&(idx, _)) = app.filtered.get(app.selected) {
+        let cmd = &app.commands[idx];
+        content.push(Line::from(Span::styled(truncate_str(&cmd.path, width), Style::default().fg(cat::YELLOW))));
+
+        // The command itself, so a similar path can't be deleted by mistake
+        let mut lines = wrap_text(&cmd.command, width);
+        if lines.len() > DELETE_PREVIEW_LINES {
+            // Fold the rest into the last line so truncation marks it with ".."
+            let rest = lines.split_off(DELETE_PREVIEW_LINES - 1).join(" ");
+            lines.push(truncate_str(&rest, width));
+        }
+        for line in lines {
+            content.push(Line::from(Span::styled(line, Style::default().fg(cat::SUBTEXT0))));
+        }
+    }
+    content.push(Line::from(""));
+    content.push(Line::from(vec![
+        Span::styled("y", Style::default().fg(cat::RED)),
+        Span::styled(" yes  ", Style::default().fg(cat::OVERLAY0)),
+        Span::styled("n", Style::default().fg(cat::OVERLAY1)),
+        Span::styled(" no", Style::default().fg(cat::OVERLAY0)),
+    ]));
+
+    f.render_widget(Paragraph::new(content).alignment(Alignment::Center), inner);
 }
 
 fn draw_help_modal(f: &mut Frame, size: Rect) {
@@ -434,6 +578,7 @@ fn draw_help_modal(f: &mut Frame, size: Rect) {
     let sections = vec![
         ("Navigation", vec![
             ("↑↓", "move"),
+            ("tab", "mark (pick --multi)"),
             ("enter", "select"),
             ("esc", "quit"),
         ]),
@@ -442,6 +587,8 @@ fn draw_help_modal(f: &mut Frame, size: Rect) {
             ("F2", "add"),
             ("F3", "edit"),
             ("F4", "delete"),
+            ("F5", "scores/matches"),
+            ("ctrl+p  :", "command palette"),
         ]),
         ("Form", vec![
             ("tab", "next field"),
@@ -475,6 +622,45 @@ fn draw_help_modal(f: &mut Frame, size: Rect) {
     f.render_widget(Paragraph::new(lines), inner);
 }
 
+fn draw_palette_modal(f: &mut Frame, app: &App, size: Rect) {
+    let modal_area = centered_rect(40, 40, size);
+
+    f.render_widget(Clear, modal_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(cat::MAUVE))
+        .title(Span::styled("─ Actions ─", Style::default().fg(cat::MAUVE)))
+        .style(Style::default().bg(cat::BASE));
+
+    f.render_widget(block.clone(), modal_area);
+    let inner = block.inner(modal_area);
+
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled(": ", Style::default().fg(cat::MAUVE)),
+            Span::styled(&app.palette_input, Style::default().fg(cat::TEXT)),
+            Span::styled("│", Style::default().fg(cat::LAVENDER)),
+        ]),
+        Line::from(""),
+    ];
+
+    for (i, (_, name, key)) in app.palette_matches().into_iter().enumerate() {
+        let (marker, style) = if i == app.palette_selected {
+            ("▌ ", Style::default().fg(cat::TEXT).bg(cat::SURFACE0))
+        } else {
+            ("  ", Style::default().fg(cat::SUBTEXT1))
+        };
+        lines.push(Line::from(vec![
+            Span::styled(marker, Style::default().fg(cat::MAUVE)),
+            Span::styled(format!("{:12}", name), style),
+            Span::styled(key, Style::default().fg(cat::PEACH)),
+        ]));
+    }
+
+    f.render_widget(Paragraph::new(lines), inner);
+}
+
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_width = r.width * percent_x / 100;
     let popup_height = r.height * percent_y / 100;
@@ -487,52 +673,247 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     }
 }
 
-fn truncate_str(s: &str, max_len: usize) -> String {
-    if s.chars().count() <= max_len {
-        s.to_string()
-    } else if max_len > 2 {
-        format!("{}..", s.chars().take(max_len - 2).collect::<String>())
-    } else {
-        s.chars().take(max_len).collect()
-    }
+/// One piece of a `[display] list_format` row.
+#[derive(Debug, PartialEq)]
+enum ListColumn {
+    Icon,
+    Path(Option<usize>),
+    Command(Option<usize>),
+    Count,
+    Text(String),
 }
 
-fn wrap_text(text: &str, max_width: usize) -> Vec<String> {
-    if max_width == 0 {
-        return vec![text.to_string()];
+const COUNT_WIDTH: usize = 4;
+
+/// Parse `{icon}`, `{path}`, `{command}` and `{count}` tokens; `{path:30}`
+/// fixes a column's width. Anything else is kept as literal text.
+fn parse_list_format(format: &str) -> Vec<ListColumn> {
+    let mut columns = Vec::new();
+    let mut text = String::new();
+    let mut rest = format;
+
+    while let Some(start) = rest.find('{') {
+        text.push_str(&rest[..start]);
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        let token = &rest[start + 1..start + len];
+        let (name, width) = match token.split_once(':') {
+            Some((name, width)) => (name, width.parse().ok()),
+            None => (token, None),
+        };
+        let column = match name {
+            "icon" => Some(ListColumn::Icon),
+            "path" => Some(ListColumn::Path(width)),
+            "command" => Some(ListColumn::Command(width)),
+            "count" => Some(ListColumn::Count),
+            _ => None,
+        };
+        match column {
+            Some(column) => {
+                if !text.is_empty() {
+                    columns.push(ListColumn::Text(std::mem::take(&mut text)));
+                }
+                columns.push(column);
+            }
+            None => text.push_str(&rest[start..=start + len]),
+        }
+        rest = &rest[start + len + 1..];
+    }
+    text.push_str(rest);
+    if !text.is_empty() {
+        columns.push(ListColumn::Text(text));
     }
 
-    let mut lines = Vec::new();
-    let mut current_line = String::new();
+    columns
+}
 
-    for word in text.split_whitespace() {
-        if current_line.is_empty() {
-            if word.len() > max_width {
-                let mut remaining = word;
-                while remaining.len() > max_width {
-                    lines.push(remaining[..max_width].to_string());
-                    remaining = &remaining[max_width..];
-                }
-                current_line = remaining.to_string();
-            } else {
-                current_line = word.to_string();
+/// Lay out one list row within `max_width` display cells. Columns without a
+/// fixed width share whatever the fixed ones leave over.
+fn list_row_spans<'a>(
+    app: &App,
+    cmd: &Command,
+    columns: &[ListColumn],
+    max_width: usize,
+    text_color: Color,
+    is_selected: bool,
+) -> Vec<Span<'a>> {
+    let (icon, icon_color) = get_category_icon(&cmd.path, &app.icons);
+
+    let mut fixed = 0;
+    let mut flexible = 0;
+    for (i, column) in columns.iter().enumerate() {
+        fixed += match column {
+            ListColumn::Icon => icon.width(),
+            ListColumn::Path(Some(w)) | ListColumn::Command(Some(w)) => *w,
+            ListColumn::Path(None) | ListColumn::Command(None) => {
+                flexible += 1;
+                0
+            }
+            ListColumn::Count => COUNT_WIDTH,
+            // Separator after a hidden icon is dropped below
+            ListColumn::Text(t) if icon.is_empty() && i > 0 && columns[i - 1] == ListColumn::Icon => {
+                t.trim_start().width()
+            }
+            ListColumn::Text(t) => t.width(),
+        };
+    }
+    let mut flex_left = max_width.saturating_sub(fixed);
+
+    let mut spans = Vec::new();
+    let mut used = 0;
+    for (i, column) in columns.iter().enumerate() {
+        let remaining = max_width.saturating_sub(used);
+        if remaining == 0 {
+            break;
+        }
+        let is_last = i == columns.len() - 1;
+
+        let (content, style) = match column {
+            ListColumn::Icon => (icon.to_string(), Style::default().fg(icon_color)),
+            ListColumn::Path(width) | ListColumn::Command(width) => {
+                let width = match width {
+                    Some(w) => *w,
+                    None => {
+                        let share = flex_left / flexible.max(1);
+                        flexible = flexible.saturating_sub(1);
+                        flex_left -= share;
+                        share
+                    }
+                };
+                let (value, style) = match column {
+                    ListColumn::Path(_) => {
+                        let style = Style::default().fg(text_color);
+                        let style = if is_selected { style.add_modifier(Modifier::BOLD) } else { style };
+                        (cmd.path.as_str(), style)
+                    }
+                    _ => (cmd.command.as_str(), Style::default().fg(cat::OVERLAY1)),
+                };
+                let value = truncate_str(value, width.min(remaining));
+                // Pad so later columns line up across rows
+                let value = if is_last { value } else { pad_str(&value, width.min(remaining)) };
+                (value, style)
             }
-        } else if current_line.len() + 1 + word.len() <= max_width {
-            current_line.push(' ');
-            current_line.push_str(word);
+            ListColumn::Count => {
+                let badge = match app.use_counts.get(&cmd.path) {
+                    Some(n) => format!("{}×", n),
+                    None => String::new(),
+                };
+                (
+                    format!("{:>width$}", badge, width = COUNT_WIDTH),
+                    Style::default().fg(cat::OVERLAY0),
+                )
+            }
+            ListColumn::Text(t) => {
+                let t = if icon.is_empty() && i > 0 && columns[i - 1] == ListColumn::Icon {
+                    t.trim_start()
+                } else {
+                    t
+                };
+                (t.to_string(), Style::default())
+            }
+        };
+
+        let content = truncate_str(&content, remaining);
+        used += content.width();
+        if content.is_empty() {
+            continue;
+        }
+
+        let source = match column {
+            ListColumn::Path(_) if app.show_scores => cmd.path.as_str(),
+            ListColumn::Command(_) if app.show_scores => cmd.command.as_str(),
+            _ => "",
+        };
+        let matched = app.match_indices(source);
+        if matched.is_empty() {
+            spans.push(Span::styled(content, style));
         } else {
-            lines.push(current_line);
-            current_line = word.to_string();
+            spans.extend(highlight_spans(&content, source, &matched, style));
+        }
+    }
+
+    spans
+}
+
+/// Split `content` into spans, emphasizing the chars at `matched` positions
+/// of `source`. Positions lost to truncation are not highlighted.
+fn highlight_spans<'a>(content: &str, source: &str, matched: &[usize], style: Style) -> Vec<Span<'a>> {
+    let hit = style.fg(cat::PEACH).add_modifier(Modifier::BOLD);
+    let mut spans = Vec::new();
+    let mut run = String::new();
+    let mut run_is_hit = false;
+
+    let source: Vec<char> = source.chars().collect();
+    for (i, c) in content.chars().enumerate() {
+        let is_hit = matched.contains(&i) && source.get(i) == Some(&c);
+        if is_hit != run_is_hit && !run.is_empty() {
+            spans.push(Span::styled(std::mem::take(&mut run), if run_is_hit { hit } else { style }));
+        }
+        run_is_hit = is_hit;
+        run.push(c);
+    }
+    if !run.is_empty() {
+        spans.push(Span::styled(run, if run_is_hit { hit } else { style }));
+    }
+
+    spans
+}
+
+fn pad_str(s: &str, width: usize) -> String {
+    format!("{}{}", s, " ".repeat(width.saturating_sub(s.width())))
+}
+
+/// Truncate to `max_len` terminal cells, marking the cut with "..".
+fn truncate_str(s: &str, max_len: usize) -> String {
+    if s.width() <= max_len {
+        return s.to_string();
+    }
+
+    let (budget, suffix) = if max_len > 2 { (max_len - 2, "..") } else { (max_len, "") };
+    let mut out = String::new();
+    let mut used = 0;
+    for c in s.chars() {
+        let w = c.width().unwrap_or(0);
+        if used + w > budget {
+            break;
         }
+        used += w;
+        out.push(c);
     }
+    out.push_str(suffix);
+    out
+}
 
-    if !current_line.is_empty() {
-        lines.push(current_line);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_list_format_tokens() {
+        assert_eq!(
+            parse_list_format("{icon} {path:20} {command} {bogus}"),
+            vec![
+                ListColumn::Icon,
+                ListColumn::Text(" ".to_string()),
+                ListColumn::Path(Some(20)),
+                ListColumn::Text(" ".to_string()),
+                ListColumn::Command(None),
+                ListColumn::Text(" {bogus}".to_string()),
+            ]
+        );
     }
 
-    if lines.is_empty() {
-        lines.push(String::new());
+    #[test]
+    fn highlights_matched_chars_only_where_visible() {
+        let spans = highlight_spans("docker/..", "docker/prune", &[0, 7, 8], Style::default());
+        let text: Vec<&str> = spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(text, ["d", "ocker/.."]);
     }
 
-    lines
+    #[test]
+    fn test_truncates_by_display_width() {
+        assert_eq!(truncate_str("日本語のパス", 6), "日本..");
+        assert_eq!(truncate_str("short", 10), "short");
+    }
 }